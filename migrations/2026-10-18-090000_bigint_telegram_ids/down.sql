@@ -0,0 +1,4 @@
+DROP INDEX users_telegram_id_idx;
+
+ALTER TABLE users
+    ALTER COLUMN telegram_id TYPE INTEGER;
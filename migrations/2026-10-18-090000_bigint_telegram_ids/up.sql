@@ -0,0 +1,37 @@
+-- Keep the oldest account for every telegram_id that got duplicated by concurrent logins,
+-- picked once so the orders are moved to the very account that is kept
+CREATE TEMPORARY TABLE kept_users AS
+SELECT DISTINCT ON (telegram_id) id, telegram_id
+FROM users
+ORDER BY telegram_id, created_at, id;
+
+-- The kept account gets the highest role of its duplicates: admin, then any other than user
+UPDATE users
+SET role = best.role
+FROM kept_users keep
+         JOIN (SELECT DISTINCT ON (telegram_id) telegram_id, role
+               FROM users
+               ORDER BY telegram_id, role = 'admin' DESC, role <> 'user' DESC) best
+              ON best.telegram_id = keep.telegram_id
+WHERE users.id = keep.id
+  AND users.role <> best.role;
+
+UPDATE orders
+SET user_id = keep.id
+FROM users dup
+         JOIN kept_users keep ON keep.telegram_id = dup.telegram_id
+WHERE orders.user_id = dup.id
+  AND dup.id <> keep.id;
+
+DELETE
+FROM users dup
+    USING kept_users keep
+WHERE keep.telegram_id = dup.telegram_id
+  AND dup.id <> keep.id;
+
+DROP TABLE kept_users;
+
+ALTER TABLE users
+    ALTER COLUMN telegram_id TYPE BIGINT;
+
+CREATE UNIQUE INDEX users_telegram_id_idx ON users (telegram_id);
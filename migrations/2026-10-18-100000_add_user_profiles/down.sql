@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS set_updated_at ON users;
+
+ALTER TABLE users
+    DROP COLUMN first_name,
+    DROP COLUMN last_name,
+    DROP COLUMN username,
+    DROP COLUMN photo_url,
+    DROP COLUMN phone_number,
+    DROP COLUMN email,
+    DROP COLUMN updated_at;
@@ -0,0 +1,10 @@
+ALTER TABLE users
+    ADD COLUMN first_name   VARCHAR(64),
+    ADD COLUMN last_name    VARCHAR(64),
+    ADD COLUMN username     VARCHAR(32),
+    ADD COLUMN photo_url    TEXT,
+    ADD COLUMN phone_number VARCHAR(20),
+    ADD COLUMN email        VARCHAR(254),
+    ADD COLUMN updated_at   TIMESTAMP;
+
+SELECT diesel_manage_updated_at('users');
@@ -0,0 +1,5 @@
+DROP TABLE user_status_events;
+
+ALTER TABLE users
+    DROP COLUMN status_reason,
+    DROP COLUMN status_until;
@@ -0,0 +1,16 @@
+ALTER TABLE users
+    ADD COLUMN status_reason TEXT,
+    ADD COLUMN status_until  TIMESTAMP;
+
+CREATE TABLE user_status_events
+(
+    id         uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id    uuid        NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    admin_id   uuid REFERENCES users (id) ON DELETE SET NULL,
+    status     VARCHAR(20) NOT NULL,
+    reason     TEXT,
+    expires_at TIMESTAMP,
+    created_at TIMESTAMP   NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX user_status_events_user_id_idx ON user_status_events (user_id);
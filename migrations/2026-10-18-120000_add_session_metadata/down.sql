@@ -0,0 +1,10 @@
+DROP INDEX sessions_expires_at_idx;
+DROP INDEX sessions_user_id_idx;
+
+ALTER TABLE sessions
+    DROP COLUMN id,
+    DROP COLUMN created_at,
+    DROP COLUMN last_seen_at,
+    DROP COLUMN expires_at,
+    DROP COLUMN user_agent,
+    DROP COLUMN ip;
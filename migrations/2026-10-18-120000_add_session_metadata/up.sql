@@ -0,0 +1,13 @@
+ALTER TABLE sessions
+    ADD COLUMN id           uuid      NOT NULL UNIQUE DEFAULT uuid_generate_v4(),
+    ADD COLUMN created_at   TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ADD COLUMN expires_at   TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP + INTERVAL '30 days',
+    ADD COLUMN user_agent   TEXT,
+    ADD COLUMN ip           VARCHAR(45);
+
+ALTER TABLE sessions
+    ALTER COLUMN expires_at DROP DEFAULT;
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
+CREATE INDEX sessions_expires_at_idx ON sessions (expires_at);
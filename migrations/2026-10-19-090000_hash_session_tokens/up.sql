@@ -0,0 +1,4 @@
+-- Sessions are now looked up by the SHA-256 digest of the token, so existing
+-- raw tokens can't be used anymore
+DELETE
+FROM sessions;
@@ -0,0 +1,15 @@
+CREATE TABLE api_tokens
+(
+    id           uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id      uuid         NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    created_by   uuid REFERENCES users (id) ON DELETE SET NULL,
+    name         VARCHAR(100) NOT NULL,
+    token_hash   BYTEA        NOT NULL UNIQUE,
+    scopes       TEXT[]       NOT NULL,
+    created_at   TIMESTAMP    NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at   TIMESTAMP    NOT NULL,
+    last_used_at TIMESTAMP,
+    revoked_at   TIMESTAMP
+);
+
+CREATE INDEX api_tokens_user_id_idx ON api_tokens (user_id);
@@ -0,0 +1,21 @@
+CREATE TABLE driver_licenses
+(
+    id                 uuid PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id            uuid        NOT NULL UNIQUE REFERENCES users (id) ON DELETE CASCADE,
+    license_number     VARCHAR(50) NOT NULL,
+    categories         TEXT[]      NOT NULL,
+    issued_at          DATE        NOT NULL,
+    expires_at         DATE        NOT NULL,
+    photos             TEXT[]      NOT NULL,
+    status             VARCHAR(20) NOT NULL DEFAULT 'pending',
+    rejection_reason   TEXT,
+    reviewed_by        uuid REFERENCES users (id) ON DELETE SET NULL,
+    reviewed_at        TIMESTAMP,
+    expiry_notified_at TIMESTAMP,
+    created_at         TIMESTAMP   NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at         TIMESTAMP   NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+SELECT diesel_manage_updated_at('driver_licenses');
+
+CREATE INDEX driver_licenses_status_expires_at_idx ON driver_licenses (status, expires_at);
@@ -0,0 +1 @@
+DROP FUNCTION rental_cost(TIMESTAMP, TIMESTAMP, INTEGER, INTEGER, INTEGER);
@@ -0,0 +1,12 @@
+-- Cost of renting a car between two moments. Started hours are billed, whole weeks and
+-- days at the weekly and daily rates, and the remainder never costs more than the next
+-- longer period would.
+CREATE FUNCTION rental_cost(start_time TIMESTAMP, end_time TIMESTAMP,
+                            hourly_rate INTEGER, daily_rate INTEGER, weekly_rate INTEGER)
+    RETURNS INTEGER AS
+$$
+SELECT (hours / 168) * weekly_rate
+           + LEAST((hours % 168 / 24) * daily_rate + LEAST((hours % 24) * hourly_rate, daily_rate),
+                   weekly_rate)
+FROM (SELECT GREATEST(CEIL(EXTRACT(EPOCH FROM end_time - start_time) / 3600), 1)::INTEGER AS hours) AS duration
+$$ LANGUAGE SQL IMMUTABLE STRICT;
@@ -0,0 +1 @@
+ALTER TABLE cars ADD COLUMN license_plate VARCHAR(20) UNIQUE;
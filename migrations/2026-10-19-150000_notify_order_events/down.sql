@@ -0,0 +1,2 @@
+DROP TRIGGER orders_notify_events ON orders;
+DROP FUNCTION notify_order_events();
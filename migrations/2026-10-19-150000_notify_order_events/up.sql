@@ -0,0 +1,43 @@
+-- Publishes the lifecycle events of orders, whichever instance or command changed them
+CREATE FUNCTION notify_order_events() RETURNS TRIGGER AS $$
+DECLARE
+    events TEXT[] := '{}';
+    event TEXT;
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        events := events || 'created'::TEXT;
+    ELSE
+        IF NEW.status IS DISTINCT FROM OLD.status THEN
+            events := events || CASE NEW.status
+                WHEN 'cancelled' THEN 'cancelled'
+                WHEN 'accepted' THEN 'accepted'
+                WHEN 'processing' THEN 'started'
+                WHEN 'finished' THEN 'finished'
+            END;
+        END IF;
+        IF NEW.paid AND NOT OLD.paid THEN
+            events := events || 'paid'::TEXT;
+        END IF;
+    END IF;
+
+    FOREACH event IN ARRAY events LOOP
+        CONTINUE WHEN event IS NULL;
+
+        PERFORM pg_notify('order_events', json_build_object(
+            'event', event,
+            'order_id', NEW.id,
+            'user_id', NEW.user_id,
+            'car_id', NEW.car_id,
+            'status', NEW.status,
+            'paid', NEW.paid,
+            'at', NOW() AT TIME ZONE 'UTC'
+        )::TEXT);
+    END LOOP;
+
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER orders_notify_events
+    AFTER INSERT OR UPDATE ON orders
+    FOR EACH ROW EXECUTE FUNCTION notify_order_events();
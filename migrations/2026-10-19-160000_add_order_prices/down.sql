@@ -0,0 +1,5 @@
+DROP TRIGGER orders_set_price ON orders;
+DROP FUNCTION set_order_price();
+
+ALTER TABLE orders
+    DROP COLUMN price;
@@ -0,0 +1,28 @@
+-- Price of the rental, fixed when it finishes so later changes to the car's rates don't
+-- alter the revenue of past rentals
+ALTER TABLE orders
+    ADD COLUMN price INTEGER;
+
+-- The rates in effect back then are unknown, finished rentals keep today's price
+UPDATE orders o
+SET price = rental_cost(o.start_rent_time, o.end_rent_time, c.hourly_rate, c.daily_rate, c.weekly_rate)
+FROM cars c
+WHERE c.id = o.car_id
+  AND o.status = 'finished';
+
+CREATE FUNCTION set_order_price() RETURNS TRIGGER AS $$
+BEGIN
+    SELECT rental_cost(NEW.start_rent_time, NEW.end_rent_time, hourly_rate, daily_rate, weekly_rate)
+    INTO NEW.price
+    FROM cars
+    WHERE id = NEW.car_id;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER orders_set_price
+    BEFORE INSERT OR UPDATE ON orders
+    FOR EACH ROW
+    WHEN (NEW.status = 'finished' AND NEW.price IS NULL)
+EXECUTE FUNCTION set_order_price();
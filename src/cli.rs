@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+
+use crate::config::Config;
+use crate::error::ServerError;
+use crate::handlers::cars::fleet::{self, FleetFormat};
+use crate::infra::db::{create_pool, get_migrations_status, revert_last_migration, run_migrations};
+use crate::infra::services::{
+    cars_service, driver_licenses_service, orders_service, sessions_service, users_service,
+};
+use crate::infra::services::cars_service::{CarsFilter, ImportCarDb};
+use crate::models::FieldError;
+use crate::models::license_status::LicenseStatus;
+
+pub const USAGE: &str = "\
+Usage: car-sharing [COMMAND]
+
+Commands:
+  serve                     Run the server (default)
+  migrate up                Run the pending migrations
+  migrate down              Revert the latest migration
+  migrate status            List the migrations and whether they've been run
+  create-admin <telegram>   Create an admin, or promote an existing user
+  approve-license <telegram>
+                            Approve a user's pending driver's license, e.g. one an
+                            admin can't have approved by another admin
+  import-cars <file> [--dry-run]
+                            Update, by id or else license plate, the cars listed in
+                            a JSON or .csv file, creating those matching neither
+  export-cars [json|csv]    Print every car, as JSON by default
+  expire-orders             Cancel orders left unconfirmed for ORDER_EXPIRE_AFTER seconds
+  sessions purge            Delete expired sessions
+  help                      Print this message";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    Migrate(MigrateCommand),
+    CreateAdmin(i64),
+    ApproveLicense(i64),
+    ImportCars { file: PathBuf, dry_run: bool },
+    ExportCars(FleetFormat),
+    ExpireOrders,
+    PurgeSessions,
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum MigrateCommand {
+    Up,
+    Down,
+    Status,
+}
+
+impl Command {
+    /// Parses the arguments following the binary's name.
+    pub fn parse(args: &[String]) -> Result<Command, String> {
+        let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        match args.as_slice() {
+            [] | ["serve"] => Ok(Command::Serve),
+            ["migrate", "up"] => Ok(Command::Migrate(MigrateCommand::Up)),
+            ["migrate", "down"] => Ok(Command::Migrate(MigrateCommand::Down)),
+            ["migrate", "status"] => Ok(Command::Migrate(MigrateCommand::Status)),
+            ["create-admin", telegram_id] => telegram_id
+                .parse::<i64>()
+                .map(Command::CreateAdmin)
+                .map_err(|_| format!("`{}` is not a Telegram id", telegram_id)),
+            ["approve-license", telegram_id] => telegram_id
+                .parse::<i64>()
+                .map(Command::ApproveLicense)
+                .map_err(|_| format!("`{}` is not a Telegram id", telegram_id)),
+            ["import-cars", file] => Ok(Command::ImportCars {
+                file: PathBuf::from(file),
+                dry_run: false,
+            }),
+            ["import-cars", file, "--dry-run"] | ["import-cars", "--dry-run", file] => {
+                Ok(Command::ImportCars {
+                    file: PathBuf::from(file),
+                    dry_run: true,
+                })
+            }
+            ["export-cars"] => Ok(Command::ExportCars(FleetFormat::Json)),
+            ["export-cars", format] => format.parse::<FleetFormat>().map(Command::ExportCars),
+            ["expire-orders"] => Ok(Command::ExpireOrders),
+            ["sessions", "purge"] => Ok(Command::PurgeSessions),
+            ["help" | "-h" | "--help"] => Ok(Command::Help),
+            _ => Err(format!("Unknown command `{}`", args.join(" "))),
+        }
+    }
+}
+
+/// Runs a one-off command against the configured database, printing its outcome.
+pub async fn run(command: Command, config: &Config) -> Result<(), ServerError> {
+    match command {
+        Command::Migrate(MigrateCommand::Up) => {
+            let versions = run_migrations(
+                config.db_url(),
+                config.db_connect_retries(),
+                config.db_connect_retry_delay(),
+            )
+            .await?;
+
+            if versions.is_empty() {
+                println!("No pending migrations");
+            }
+            for version in versions {
+                println!("Ran {}", version);
+            }
+        }
+        Command::Migrate(MigrateCommand::Down) => {
+            let version = revert_last_migration(config.db_url()).await?;
+
+            println!("Reverted {}", version);
+        }
+        Command::Migrate(MigrateCommand::Status) => {
+            for (name, applied) in get_migrations_status(config.db_url()).await? {
+                println!("[{}] {}", if applied { "x" } else { " " }, name);
+            }
+        }
+        Command::CreateAdmin(telegram_id) => {
+            let pool = create_pool(config).await?;
+
+            let admin = users_service::create_admin(&pool, telegram_id)
+                .await
+                .map_err(ServerError::Command)?;
+
+            println!("User {} with Telegram id {} is an admin", admin.id, telegram_id);
+        }
+        Command::ApproveLicense(telegram_id) => {
+            let pool = create_pool(config).await?;
+
+            let license = driver_licenses_service::get_by_telegram_id(&pool, telegram_id)
+                .await
+                .map_err(ServerError::Command)?;
+
+            driver_licenses_service::review(&pool, license.id, None, LicenseStatus::Approved, None)
+                .await
+                .map_err(ServerError::Command)?;
+
+            println!("Approved driver's license {} of Telegram id {}", license.id, telegram_id);
+        }
+        Command::ImportCars { file, dry_run } => {
+            let rows = read_cars(&file)?;
+            let pool = create_pool(config).await?;
+
+            let report = cars_service::import(&pool, rows, dry_run)
+                .await
+                .map_err(ServerError::Command)?;
+
+            if !report.errors.is_empty() {
+                return Err(invalid_file(&file, report.errors));
+            }
+
+            if dry_run {
+                println!("Would create {} and update {} cars", report.created, report.updated);
+            } else {
+                println!("Created {} and updated {} cars", report.created, report.updated);
+            }
+        }
+        Command::ExportCars(format) => {
+            let pool = create_pool(config).await?;
+
+            let cars = cars_service::get_all(&pool, CarsFilter::default())
+                .await
+                .map_err(ServerError::Command)?;
+
+            print!("{}", fleet::write_fleet(format, &cars));
+        }
+        Command::ExpireOrders => {
+            let pool = create_pool(config).await?;
+
+            let created_before =
+                Utc::now().naive_utc() - chrono::Duration::seconds(config.order_expire_after());
+
+            let expired = orders_service::expire_unconfirmed(&pool, created_before)
+                .await
+                .map_err(ServerError::Command)?;
+
+            println!("Cancelled {} unconfirmed orders", expired);
+        }
+        Command::PurgeSessions => {
+            let pool = create_pool(config).await?;
+
+            let purged = sessions_service::delete_expired(&pool)
+                .await
+                .map_err(ServerError::Command)?;
+
+            println!("Purged {} expired sessions", purged);
+        }
+        Command::Serve | Command::Help => {}
+    }
+
+    Ok(())
+}
+
+/// Reads and validates the whole file before anything is imported.
+fn read_cars(file: &PathBuf) -> Result<Vec<ImportCarDb>, ServerError> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|err| ServerError::InvalidInput(format!("{}: {}", file.display(), err)))?;
+
+    fleet::read_fleet(FleetFormat::from_path(file), &content)
+        .map_err(|errors| invalid_file(file, errors))
+}
+
+fn invalid_file(file: &Path, errors: Vec<FieldError>) -> ServerError {
+    let errors = errors
+        .into_iter()
+        .map(|error| format!("{} {}", error.field, error.message))
+        .collect::<Vec<String>>();
+
+    ServerError::InvalidInput(format!("{}: {}", file.display(), errors.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<String>>())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Ok(Command::Serve), parse(&[]));
+        assert_eq!(Ok(Command::Migrate(MigrateCommand::Status)), parse(&["migrate", "status"]));
+        assert_eq!(Ok(Command::CreateAdmin(443621429)), parse(&["create-admin", "443621429"]));
+        assert_eq!(Ok(Command::PurgeSessions), parse(&["sessions", "purge"]));
+        assert_eq!(
+            Ok(Command::ImportCars { file: PathBuf::from("cars.csv"), dry_run: true }),
+            parse(&["import-cars", "cars.csv", "--dry-run"])
+        );
+        assert_eq!(Ok(Command::ExportCars(FleetFormat::Csv)), parse(&["export-cars", "csv"]));
+        assert!(parse(&["export-cars", "xml"]).is_err());
+        assert!(parse(&["create-admin", "maxud"]).is_err());
+        assert_eq!(Ok(Command::ApproveLicense(443621429)), parse(&["approve-license", "443621429"]));
+        assert!(parse(&["migrate"]).is_err());
+    }
+}
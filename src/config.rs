@@ -1,32 +1,157 @@
+use std::collections::HashMap;
 use std::env;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
 
 use dotenvy::dotenv;
 use tokio::sync::OnceCell;
+use tower_cookies::cookie::SameSite;
+use tracing_subscriber::EnvFilter;
+
+/// Keeps secrets out of `Debug` output, so the configuration can be logged safely.
+#[derive(Clone)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[redacted]")
+    }
+}
 
 #[derive(Debug)]
 struct DatabaseConfig {
-    url: String,
+    url: Secret,
+    pool_max_size: u32,
+    pool_min_idle: Option<u32>,
+    connection_timeout: u64,
+    idle_timeout: u64,
+    connect_retries: u32,
+    connect_retry_delay: u64,
 }
 
 #[derive(Debug)]
 struct ServerConfig {
     host: String,
     port: u16,
+    metrics_port: Option<u16>,
+    request_timeout: u64,
+    shutdown_timeout: u64,
+}
+
+#[derive(Debug)]
+struct SessionConfig {
+    lifetime: i64,
+    idle_timeout: i64,
+    cleanup_interval: u64,
+}
+
+#[derive(Debug)]
+struct CookieConfig {
+    same_site: SameSite,
+    domain: Option<String>,
+    secure: bool,
+}
+
+#[derive(Debug)]
+struct RateLimitConfig {
+    requests: u32,
+    window: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+struct LogConfig {
+    level: String,
+    format: LogFormat,
+}
+
+#[derive(Debug)]
+struct LicenseConfig {
+    expiry_notice: i64,
+    check_interval: u64,
+}
+
+#[derive(Debug)]
+struct OrdersConfig {
+    expire_after: i64,
+}
+
+/// Optional parts of the service, all enabled by default.
+#[derive(Debug)]
+struct FeaturesConfig {
+    swagger_ui: bool,
+    metrics: bool,
+    rate_limit: bool,
+    license_required: bool,
+    license_expiry_notifications: bool,
 }
 
 #[derive(Debug)]
 pub struct Config {
     server: ServerConfig,
     db: DatabaseConfig,
-    bot_token: String,
-    admin_ids: String,
+    session: SessionConfig,
+    cookie: CookieConfig,
+    rate_limit: RateLimitConfig,
+    log: LogConfig,
+    license: LicenseConfig,
+    orders: OrdersConfig,
+    features: FeaturesConfig,
+    cors_allowed_origins: Vec<String>,
+    csrf_trusted_origins: Vec<String>,
+    bot_token: Secret,
+    admin_ids: Vec<i64>,
+    telegram_auth_max_age: i64,
 }
 
 impl Config {
     pub fn db_url(&self) -> &str {
-        &self.db.url
+        self.db.url.expose()
+    }
+
+    /// Maximum number of connections kept by the pool.
+    pub fn db_pool_max_size(&self) -> u32 {
+        self.db.pool_max_size
+    }
+
+    /// Idle connections the pool keeps open, as many as it needs when unset.
+    pub fn db_pool_min_idle(&self) -> Option<u32> {
+        self.db.pool_min_idle
+    }
+
+    /// Seconds to wait for a connection from the pool.
+    pub fn db_connection_timeout(&self) -> u64 {
+        self.db.connection_timeout
     }
-    pub fn admin_ids(&self) -> &str {
+
+    /// Seconds after which idle pooled connections are closed.
+    pub fn db_idle_timeout(&self) -> u64 {
+        self.db.idle_timeout
+    }
+
+    /// Times to retry connecting to the database while it's starting up.
+    pub fn db_connect_retries(&self) -> u32 {
+        self.db.connect_retries
+    }
+
+    /// Seconds between attempts to connect to the database.
+    pub fn db_connect_retry_delay(&self) -> u64 {
+        self.db.connect_retry_delay
+    }
+
+    /// Telegram ids promoted to admins while the database has no admins yet.
+    pub fn admin_ids(&self) -> &[i64] {
         &self.admin_ids
     }
 
@@ -38,37 +163,529 @@ impl Config {
         &self.server.host
     }
 
+    /// Port serving `/metrics` without authentication. When unset the metrics are served
+    /// on the API port to users allowed to read them.
+    pub fn metrics_port(&self) -> Option<u16> {
+        self.server.metrics_port
+    }
+
+    /// Seconds a request may take before it's answered with a timeout error.
+    pub fn request_timeout(&self) -> u64 {
+        self.server.request_timeout
+    }
+
+    /// Seconds given to in-flight requests and background jobs to finish on shutdown.
+    pub fn shutdown_timeout(&self) -> u64 {
+        self.server.shutdown_timeout
+    }
+
     pub fn bot_token(&self) -> &str {
-        &self.bot_token
+        self.bot_token.expose()
+    }
+
+    /// Absolute lifetime of a session in seconds.
+    pub fn session_lifetime(&self) -> i64 {
+        self.session.lifetime
+    }
+
+    /// Seconds of inactivity after which a session expires.
+    pub fn session_idle_timeout(&self) -> i64 {
+        self.session.idle_timeout
+    }
+
+    /// Seconds between purges of expired sessions.
+    pub fn session_cleanup_interval(&self) -> u64 {
+        self.session.cleanup_interval
+    }
+
+    /// Days before a driver's license expires to warn its owner.
+    pub fn license_expiry_notice(&self) -> i64 {
+        self.license.expiry_notice
+    }
+
+    /// Seconds between checks for expiring driver's licenses.
+    pub fn license_check_interval(&self) -> u64 {
+        self.license.check_interval
+    }
+
+    /// Seconds after which unconfirmed orders are cancelled by `expire-orders`.
+    pub fn order_expire_after(&self) -> i64 {
+        self.orders.expire_after
+    }
+
+    pub fn cookie_same_site(&self) -> SameSite {
+        self.cookie.same_site
+    }
+
+    pub fn cookie_domain(&self) -> Option<&str> {
+        self.cookie.domain.as_deref()
+    }
+
+    pub fn cookie_secure(&self) -> bool {
+        self.cookie.secure
+    }
+
+    /// Origins allowed to call the API from browsers, CORS is disabled when empty.
+    pub fn cors_allowed_origins(&self) -> &[String] {
+        &self.cors_allowed_origins
+    }
+
+    /// Origins besides the API's own host allowed to send mutating requests.
+    pub fn csrf_trusted_origins(&self) -> &[String] {
+        &self.csrf_trusted_origins
+    }
+
+    /// Requests a client IP may make per rate limit window.
+    pub fn rate_limit_requests(&self) -> u32 {
+        self.rate_limit.requests
+    }
+
+    /// Length of the rate limit window in seconds.
+    pub fn rate_limit_window(&self) -> u64 {
+        self.rate_limit.window
+    }
+
+    /// Log filter used unless `RUST_LOG` is set, e.g. `info,car_sharing=debug`.
+    pub fn log_level(&self) -> &str {
+        &self.log.level
+    }
+
+    /// Whether logs are written as plain text or as one JSON object per line.
+    pub fn log_format(&self) -> LogFormat {
+        self.log.format
+    }
+
+    /// Maximum age of a Telegram login payload in seconds.
+    pub fn telegram_auth_max_age(&self) -> i64 {
+        self.telegram_auth_max_age
+    }
+
+    pub fn swagger_ui_enabled(&self) -> bool {
+        self.features.swagger_ui
+    }
+
+    pub fn metrics_enabled(&self) -> bool {
+        self.features.metrics
+    }
+
+    pub fn rate_limit_enabled(&self) -> bool {
+        self.features.rate_limit
+    }
+
+    /// Whether placing an order requires an approved driver's license.
+    pub fn license_required(&self) -> bool {
+        self.features.license_required
+    }
+
+    pub fn license_expiry_notifications_enabled(&self) -> bool {
+        self.features.license_expiry_notifications
+    }
+
+    /// Checks that need more than one setting.
+    fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.server.metrics_port == Some(self.server.port) {
+            errors.push("METRICS_PORT (server.metrics_port) must differ from PORT".to_string());
+        }
+        if self.db.pool_max_size == 0 {
+            errors.push("DATABASE_POOL_MAX_SIZE (database.pool_max_size) must be at least 1".to_string());
+        }
+        if self.db.pool_min_idle.is_some_and(|min_idle| min_idle > self.db.pool_max_size) {
+            errors.push(
+                "DATABASE_POOL_MIN_IDLE (database.pool_min_idle) must not exceed the pool's max size"
+                    .to_string(),
+            );
+        }
+        if self.session.idle_timeout > self.session.lifetime {
+            errors.push(
+                "SESSION_IDLE_TIMEOUT (session.idle_timeout) must not exceed SESSION_LIFETIME"
+                    .to_string(),
+            );
+        }
+        if self.session.cleanup_interval == 0 {
+            errors.push("SESSION_CLEANUP_INTERVAL (session.cleanup_interval) must be positive".to_string());
+        }
+        if self.features.license_expiry_notifications && self.license.check_interval == 0 {
+            errors.push("LICENSE_CHECK_INTERVAL (license.check_interval) must be positive".to_string());
+        }
+        if self.cookie.same_site == SameSite::None && !self.cookie.secure {
+            errors.push("COOKIE_SAME_SITE (cookie.same_site) none requires COOKIE_SECURE".to_string());
+        }
+        if self.features.rate_limit && (self.rate_limit.requests == 0 || self.rate_limit.window == 0) {
+            errors.push(
+                "RATE_LIMIT_REQUESTS and RATE_LIMIT_WINDOW (rate_limit) must be positive".to_string(),
+            );
+        }
+        for origin in &self.cors_allowed_origins {
+            if !origin.starts_with("http://") && !origin.starts_with("https://") {
+                errors.push(format!("CORS_ALLOWED_ORIGINS (cors.allowed_origins): `{}` is not an origin", origin));
+            }
+        }
+        if let Err(err) = EnvFilter::try_new(&self.log.level) {
+            errors.push(format!("RUST_LOG (log.level) is not a valid filter: {}", err));
+        }
+
+        errors
     }
 }
 
-pub static CONFIG: OnceCell<Config> = OnceCell::const_new();
+/// Every problem found while loading the configuration.
+#[derive(Debug)]
+pub struct ConfigError(Vec<String>);
 
-async fn init_config() -> Config {
-    // Load environment variables from a .env file
-    dotenv().ok();
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for error in &self.0 {
+            writeln!(f, "  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads settings from the environment, falling back to the TOML file and then to the
+/// defaults. Problems are collected instead of failing on the first one.
+struct Loader {
+    file: toml::Table,
+    env: HashMap<String, String>,
+    errors: Vec<String>,
+}
+
+impl Loader {
+    fn new(file: toml::Table, env: HashMap<String, String>) -> Self {
+        Loader {
+            file,
+            env,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Raw value of a setting, empty environment variables count as unset.
+    fn raw(&mut self, env_key: &str, file_key: &str) -> Option<String> {
+        if let Some(value) = self.env.get(env_key).filter(|value| !value.is_empty()) {
+            return Some(value.clone());
+        }
+
+        let mut path = file_key.split('.');
+        let mut value = self.file.get(path.next()?)?;
+        for key in path {
+            value = value.get(key)?;
+        }
+
+        match value {
+            toml::Value::String(value) => Some(value.clone()),
+            toml::Value::Integer(value) => Some(value.to_string()),
+            toml::Value::Boolean(value) => Some(value.to_string()),
+            toml::Value::Array(values) => Some(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        toml::Value::String(value) => value.clone(),
+                        value => value.to_string(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            _ => {
+                self.errors.push(format!("{} ({}) has an unsupported type", env_key, file_key));
+                None
+            }
+        }
+    }
+
+    fn optional_with<T>(
+        &mut self,
+        env_key: &str,
+        file_key: &str,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        let raw = self.raw(env_key, file_key)?;
+
+        match parse(raw.trim()) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.errors.push(format!("{} ({}): invalid value `{}`: {}", env_key, file_key, raw, err));
+                None
+            }
+        }
+    }
+
+    fn optional<T: FromStr>(&mut self, env_key: &str, file_key: &str) -> Option<T>
+    where
+        T::Err: Display,
+    {
+        self.optional_with(env_key, file_key, |raw| raw.parse::<T>().map_err(|err| err.to_string()))
+    }
+
+    fn parse<T: FromStr>(&mut self, env_key: &str, file_key: &str, default: T) -> T
+    where
+        T::Err: Display,
+    {
+        self.optional(env_key, file_key).unwrap_or(default)
+    }
+
+    fn required(&mut self, env_key: &str, file_key: &str) -> String {
+        self.raw(env_key, file_key).unwrap_or_else(|| {
+            self.errors.push(format!("{} ({}) must be set", env_key, file_key));
+            String::new()
+        })
+    }
+
+    fn list<T: FromStr>(&mut self, env_key: &str, file_key: &str) -> Vec<T>
+    where
+        T::Err: Display,
+    {
+        self.optional_with(env_key, file_key, |raw| {
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<T>().map_err(|err| err.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+}
+
+fn parse_same_site(value: &str) -> Result<SameSite, String> {
+    match value.to_lowercase().as_str() {
+        "strict" => Ok(SameSite::Strict),
+        "lax" => Ok(SameSite::Lax),
+        "none" => Ok(SameSite::None),
+        _ => Err("expected one of strict, lax or none".to_string()),
+    }
+}
+
+fn parse_log_format(value: &str) -> Result<LogFormat, String> {
+    match value.to_lowercase().as_str() {
+        "text" => Ok(LogFormat::Text),
+        "json" => Ok(LogFormat::Json),
+        _ => Err("expected one of text or json".to_string()),
+    }
+}
+
+fn load(file: toml::Table, env: HashMap<String, String>) -> Result<Config, ConfigError> {
+    let mut loader = Loader::new(file, env);
 
     let server_config = ServerConfig {
-        host: env::var("HOST").unwrap_or_else(|_| String::from("127.0.0.1")),
-        port: env::var("PORT")
-            .unwrap_or_else(|_| String::from("0606"))
-            .parse::<u16>()
-            .unwrap(),
+        host: loader.parse("HOST", "server.host", String::from("127.0.0.1")),
+        // The `0606` of env.example and docker-compose.yml
+        port: loader.parse("PORT", "server.port", 606),
+        metrics_port: loader.optional("METRICS_PORT", "server.metrics_port"),
+        request_timeout: loader.parse("REQUEST_TIMEOUT", "server.request_timeout", 30),
+        shutdown_timeout: loader.parse("SHUTDOWN_TIMEOUT", "server.shutdown_timeout", 30),
     };
 
     let database_config = DatabaseConfig {
-        url: env::var("DATABASE_URL").expect("DATABASE_URL must be set"),
+        url: Secret(loader.required("DATABASE_URL", "database.url")),
+        pool_max_size: loader.parse("DATABASE_POOL_MAX_SIZE", "database.pool_max_size", 10),
+        pool_min_idle: loader.optional("DATABASE_POOL_MIN_IDLE", "database.pool_min_idle"),
+        connection_timeout: loader.parse(
+            "DATABASE_CONNECTION_TIMEOUT",
+            "database.connection_timeout",
+            30,
+        ),
+        idle_timeout: loader.parse("DATABASE_IDLE_TIMEOUT", "database.idle_timeout", 600),
+        connect_retries: loader.parse("DATABASE_CONNECT_RETRIES", "database.connect_retries", 10),
+        connect_retry_delay: loader.parse(
+            "DATABASE_CONNECT_RETRY_DELAY",
+            "database.connect_retry_delay",
+            2,
+        ),
+    };
+
+    let session_config = SessionConfig {
+        lifetime: loader.parse("SESSION_LIFETIME", "session.lifetime", 2592000),
+        idle_timeout: loader.parse("SESSION_IDLE_TIMEOUT", "session.idle_timeout", 604800),
+        cleanup_interval: loader.parse("SESSION_CLEANUP_INTERVAL", "session.cleanup_interval", 3600),
+    };
+
+    let cookie_config = CookieConfig {
+        same_site: loader
+            .optional_with("COOKIE_SAME_SITE", "cookie.same_site", parse_same_site)
+            .unwrap_or(SameSite::Lax),
+        domain: loader.optional("COOKIE_DOMAIN", "cookie.domain"),
+        secure: loader.parse("COOKIE_SECURE", "cookie.secure", true),
+    };
+
+    let rate_limit_config = RateLimitConfig {
+        requests: loader.parse("RATE_LIMIT_REQUESTS", "rate_limit.requests", 300),
+        window: loader.parse("RATE_LIMIT_WINDOW", "rate_limit.window", 60),
+    };
+
+    let log_config = LogConfig {
+        level: loader.parse("RUST_LOG", "log.level", String::from("info")),
+        format: loader
+            .optional_with("LOG_FORMAT", "log.format", parse_log_format)
+            .unwrap_or(LogFormat::Text),
+    };
+
+    let license_config = LicenseConfig {
+        expiry_notice: loader.parse("LICENSE_EXPIRY_NOTICE", "license.expiry_notice", 30),
+        check_interval: loader.parse("LICENSE_CHECK_INTERVAL", "license.check_interval", 86400),
+    };
+
+    let orders_config = OrdersConfig {
+        expire_after: loader.parse("ORDER_EXPIRE_AFTER", "orders.expire_after", 86400),
+    };
+
+    let features_config = FeaturesConfig {
+        swagger_ui: loader.parse("FEATURE_SWAGGER_UI", "features.swagger_ui", true),
+        metrics: loader.parse("FEATURE_METRICS", "features.metrics", true),
+        rate_limit: loader.parse("FEATURE_RATE_LIMIT", "features.rate_limit", false),
+        license_required: loader.parse("FEATURE_LICENSE_REQUIRED", "features.license_required", true),
+        license_expiry_notifications: loader.parse(
+            "FEATURE_LICENSE_EXPIRY_NOTIFICATIONS",
+            "features.license_expiry_notifications",
+            true,
+        ),
     };
 
-    Config {
+    let config = Config {
         server: server_config,
         db: database_config,
-        bot_token: env::var("BOT_TOKEN").expect("BOT_TOKEN must be set"),
-        admin_ids: env::var("ADMIN_IDS").expect("ADMIN_IDS must be set"),
+        session: session_config,
+        cookie: cookie_config,
+        rate_limit: rate_limit_config,
+        log: log_config,
+        license: license_config,
+        orders: orders_config,
+        features: features_config,
+        cors_allowed_origins: loader
+            .list::<String>("CORS_ALLOWED_ORIGINS", "cors.allowed_origins")
+            .into_iter()
+            .map(|origin| origin.trim_end_matches('/').to_string())
+            .collect(),
+        csrf_trusted_origins: loader
+            .list::<String>("CSRF_TRUSTED_ORIGINS", "csrf.trusted_origins")
+            .into_iter()
+            .map(|origin| origin.trim_end_matches('/').to_string())
+            .collect(),
+        bot_token: Secret(loader.required("BOT_TOKEN", "telegram.bot_token")),
+        admin_ids: loader.list("ADMIN_IDS", "telegram.admin_ids"),
+        telegram_auth_max_age: loader.parse("TELEGRAM_AUTH_MAX_AGE", "telegram.auth_max_age", 86400),
+    };
+
+    let mut errors = loader.errors;
+    errors.extend(config.validate());
+
+    if errors.is_empty() {
+        Ok(config)
+    } else {
+        Err(ConfigError(errors))
     }
 }
 
+/// Reads the TOML file at `CONFIG_FILE`, or `config.toml` when it exists.
+fn read_config_file() -> Result<toml::Table, ConfigError> {
+    let (path, required) = match env::var("CONFIG_FILE") {
+        Ok(path) => (path, true),
+        Err(_) => (String::from("config.toml"), false),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => content
+            .parse::<toml::Table>()
+            .map_err(|err| ConfigError(vec![format!("{}: {}", path, err)])),
+        Err(err) if required || err.kind() != std::io::ErrorKind::NotFound => {
+            Err(ConfigError(vec![format!("{}: {}", path, err)]))
+        }
+        Err(_) => Ok(toml::Table::new()),
+    }
+}
+
+pub static CONFIG: OnceCell<Config> = OnceCell::const_new();
+
+async fn init_config() -> Result<Config, ConfigError> {
+    // Load environment variables from a .env file
+    dotenv().ok();
+
+    load(read_config_file()?, env::vars().collect())
+}
+
+/// Loads the configuration, reporting every invalid setting at once.
+pub async fn try_config() -> Result<&'static Config, ConfigError> {
+    CONFIG.get_or_try_init(init_config).await
+}
+
 pub async fn config() -> &'static Config {
-    CONFIG.get_or_init(init_config).await
+    match try_config().await {
+        Ok(config) => config,
+        Err(err) => panic!("{}", err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, String> {
+        vars.iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    const REQUIRED: &[(&str, &str)] = &[("DATABASE_URL", "postgres://localhost/db"), ("BOT_TOKEN", "secret-token")];
+
+    #[test]
+    fn test_defaults() {
+        let config = load(toml::Table::new(), env(REQUIRED)).unwrap();
+
+        assert_eq!(606, config.server_port());
+        assert_eq!("127.0.0.1", config.server_host());
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let file = "[server]\nport = 9000\nhost = \"0.0.0.0\"\n[features]\nswagger_ui = false\n"
+            .parse::<toml::Table>()
+            .unwrap();
+
+        let mut vars = REQUIRED.to_vec();
+        vars.push(("PORT", "9100"));
+
+        let config = load(file, env(&vars)).unwrap();
+
+        assert_eq!(9100, config.server_port());
+        assert_eq!("0.0.0.0", config.server_host());
+        assert!(!config.swagger_ui_enabled());
+        assert_eq!(10, config.db_pool_max_size());
+    }
+
+    #[test]
+    fn test_reports_all_errors() {
+        let vars = env(&[("PORT", "http"), ("COOKIE_SAME_SITE", "sometimes")]);
+
+        let err = load(toml::Table::new(), vars).unwrap_err();
+
+        // Both required settings are missing and two values are invalid
+        assert_eq!(4, err.0.len());
+        assert!(err.to_string().contains("PORT (server.port)"));
+    }
+
+    #[test]
+    fn test_rejects_zero_intervals() {
+        let mut vars = REQUIRED.to_vec();
+        vars.push(("SESSION_CLEANUP_INTERVAL", "0"));
+        vars.push(("LICENSE_CHECK_INTERVAL", "0"));
+
+        let err = load(toml::Table::new(), env(&vars)).unwrap_err();
+        assert_eq!(2, err.0.len());
+
+        // The license check only runs along with the notifications
+        vars.retain(|(key, _)| *key != "SESSION_CLEANUP_INTERVAL");
+        vars.push(("FEATURE_LICENSE_EXPIRY_NOTIFICATIONS", "false"));
+        assert!(load(toml::Table::new(), env(&vars)).is_ok());
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let config = load(toml::Table::new(), env(REQUIRED)).unwrap();
+
+        let debug = format!("{:?}", config);
+
+        assert!(!debug.contains("secret-token"));
+        assert!(!debug.contains("postgres://"));
+        assert_eq!("secret-token", config.bot_token());
+    }
 }
@@ -1,11 +1,22 @@
 use std::fmt::Formatter;
-use std::num::ParseIntError;
+
+use diesel::result::DatabaseErrorKind;
 
 #[derive(Debug)]
 pub enum CarSharingError {
     DatabaseDieselError(diesel::result::Error),
-    DatabaseIntParsingError(ParseIntError),
     DatabaseNotFound,
+    /// A unique or foreign key constraint was violated.
+    DatabaseConflict,
+    InvalidSessionToken,
+    /// The change would leave no active admin.
+    LastAdmin,
+    /// Reviewers can't review their own driver's license.
+    OwnLicense,
+    /// The driver's license was already reviewed.
+    LicenseNotPending,
+    /// The order is already finished or cancelled.
+    OrderClosed,
 }
 
 pub type Result<T> = std::result::Result<T, CarSharingError>;
@@ -13,7 +24,14 @@ pub type Result<T> = std::result::Result<T, CarSharingError>;
 impl std::fmt::Display for CarSharingError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match &self {
-            _ => write!(f, "Error"),
+            CarSharingError::DatabaseDieselError(err) => write!(f, "Database error: {}", err),
+            CarSharingError::DatabaseNotFound => write!(f, "Record not found"),
+            CarSharingError::DatabaseConflict => write!(f, "Record conflicts with existing data"),
+            CarSharingError::InvalidSessionToken => write!(f, "Invalid session token"),
+            CarSharingError::LastAdmin => write!(f, "No other active admin would remain"),
+            CarSharingError::OwnLicense => write!(f, "Reviewer owns the driver's license"),
+            CarSharingError::LicenseNotPending => write!(f, "Driver's license isn't pending"),
+            CarSharingError::OrderClosed => write!(f, "Order is finished or cancelled"),
         }
     }
 }
@@ -22,13 +40,60 @@ impl From<diesel::result::Error> for CarSharingError {
     fn from(err: diesel::result::Error) -> Self {
         match err {
             diesel::result::Error::NotFound => CarSharingError::DatabaseNotFound,
+            diesel::result::Error::DatabaseError(
+                DatabaseErrorKind::UniqueViolation | DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            ) => CarSharingError::DatabaseConflict,
             _ => CarSharingError::DatabaseDieselError(err),
         }
     }
 }
 
-impl From<ParseIntError> for CarSharingError {
-    fn from(value: ParseIntError) -> Self {
-        CarSharingError::DatabaseIntParsingError(value)
+/// Errors ending the process, either while serving or running a command, each exiting
+/// with its own code.
+#[derive(Debug)]
+pub enum ServerError {
+    DatabaseUnreachable(diesel::ConnectionError),
+    Migrations(String),
+    Pool(diesel_async::pooled_connection::PoolError),
+    BootstrapAdmins(CarSharingError),
+    /// A command's input, e.g. a file to import, is invalid.
+    InvalidInput(String),
+    Command(CarSharingError),
+    Signal(std::io::Error),
+    Bind(String, std::io::Error),
+    Serve(std::io::Error),
+}
+
+impl ServerError {
+    /// Exit codes follow sysexits.h.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ServerError::DatabaseUnreachable(_) | ServerError::Pool(_) => 69,
+            ServerError::InvalidInput(_) => 65,
+            ServerError::Migrations(_)
+            | ServerError::BootstrapAdmins(_)
+            | ServerError::Command(_) => 70,
+            ServerError::Signal(_) | ServerError::Bind(..) => 71,
+            ServerError::Serve(_) => 74,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            ServerError::DatabaseUnreachable(err) => {
+                write!(f, "Couldn't connect to the database: {}", err)
+            }
+            ServerError::Migrations(err) => write!(f, "Failed to run migrations: {}", err),
+            ServerError::Pool(err) => write!(f, "Failed to create the connection pool: {}", err),
+            ServerError::BootstrapAdmins(err) => write!(f, "Failed to bootstrap admins: {}", err),
+            ServerError::InvalidInput(err) => write!(f, "Invalid input: {}", err),
+            ServerError::Command(err) => write!(f, "Command failed: {}", err),
+            ServerError::Signal(err) => write!(f, "Failed to listen for signals: {}", err),
+            ServerError::Bind(address, err) => write!(f, "Failed to bind {}: {}", address, err),
+            ServerError::Serve(err) => write!(f, "Server failed: {}", err),
+        }
     }
 }
@@ -0,0 +1,54 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use tracing::debug;
+
+use crate::handlers::api_tokens::{CreateApiTokenRequest, CreatedApiTokenResponse};
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::{api_tokens_service, api_tokens_service::NewApiTokenDb};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tokens",
+    tag = "api_tokens",
+    summary = "Create an API token",
+    request_body = CreateApiTokenRequest,
+    responses(
+        (status = 200, description = "Created API token, the token is only shown once", body = CreatedApiTokenResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 409, description = "Conflicts with existing data", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn create_api_token(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    ValidJson(payload): ValidJson<CreateApiTokenRequest>,
+) -> Result<Json<CreatedApiTokenResponse>, HandlerError> {
+    debug!("->> {:<12} - create_api_token", "HANDLER");
+
+    let new_api_token = NewApiTokenDb {
+        user_id: payload.user_id.unwrap_or(user_data.user_id),
+        created_by: Option::from(user_data.user_id),
+        name: payload.name,
+        scopes: payload
+            .scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect(),
+        expires_at: payload.expires_at,
+    };
+
+    let (api_token, token) = api_tokens_service::insert(&pool, new_api_token)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(CreatedApiTokenResponse {
+        api_token,
+        token: token.into_header_value(),
+    }))
+}
@@ -0,0 +1,34 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use tracing::debug;
+
+use crate::handlers::api_tokens::ApiTokenResponse;
+use crate::handlers::DbPool;
+use crate::infra::services::{api_tokens_service, api_tokens_service::ApiTokensFilter};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/tokens",
+    tag = "api_tokens",
+    summary = "List API tokens",
+    params(ApiTokensFilter),
+    responses(
+        (status = 200, description = "API tokens", body = Vec<ApiTokenResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn list_api_tokens(
+    State(pool): State<DbPool>,
+    Query(params): Query<ApiTokensFilter>,
+) -> Result<Json<Vec<ApiTokenResponse>>, HandlerError> {
+    debug!("->> {:<12} - list_api_tokens", "HANDLER");
+
+    let api_tokens = api_tokens_service::get_all(&pool, params)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(api_tokens))
+}
@@ -0,0 +1,82 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::handlers::validation::{check_max_length, check_not_blank, Validate};
+use crate::infra::services::api_tokens_service::ApiTokenDb;
+use crate::models::FieldError;
+use crate::models::permission::Permission;
+
+pub mod create_api_token;
+pub mod list_api_tokens;
+pub mod revoke_api_token;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiTokenResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub scopes: Vec<Permission>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+impl From<ApiTokenDb> for ApiTokenResponse {
+    fn from(api_token_db: ApiTokenDb) -> Self {
+        ApiTokenResponse {
+            id: api_token_db.id,
+            user_id: api_token_db.user_id,
+            created_by: api_token_db.created_by,
+            name: api_token_db.name,
+            scopes: api_token_db
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse().ok())
+                .collect(),
+            created_at: api_token_db.created_at,
+            expires_at: api_token_db.expires_at,
+            last_used_at: api_token_db.last_used_at,
+            revoked_at: api_token_db.revoked_at,
+        }
+    }
+}
+
+/// Returned once on creation, the token itself can't be retrieved later.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiTokenResponse {
+    #[serde(flatten)]
+    pub api_token: ApiTokenResponse,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CreateApiTokenRequest {
+    pub name: String,
+    /// Owner of the token, the creator by default.
+    pub user_id: Option<Uuid>,
+    pub scopes: Vec<Permission>,
+    pub expires_at: NaiveDateTime,
+}
+
+impl Validate for CreateApiTokenRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_not_blank(&mut errors, "name", &self.name);
+        check_max_length(&mut errors, "name", &self.name, 100);
+
+        if self.scopes.is_empty() {
+            errors.push(FieldError::new("scopes", "must not be empty"));
+        }
+        if self.expires_at <= Utc::now().naive_utc() {
+            errors.push(FieldError::new("expires_at", "must be in the future"));
+        }
+
+        errors
+    }
+}
@@ -0,0 +1,36 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::api_tokens::ApiTokenResponse;
+use crate::handlers::DbPool;
+use crate::infra::services::api_tokens_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/tokens/{id}",
+    tag = "api_tokens",
+    summary = "Revoke an API token",
+    params(("id" = Uuid, Path, description = "API token id")),
+    responses(
+        (status = 200, description = "Revoked API token", body = ApiTokenResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn revoke_api_token(
+    State(pool): State<DbPool>,
+    Path(api_token_id): Path<Uuid>,
+) -> Result<Json<ApiTokenResponse>, HandlerError> {
+    debug!("->> {:<12} - revoke_api_token", "HANDLER");
+
+    let api_token = api_tokens_service::revoke(&pool, api_token_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(api_token))
+}
@@ -1,118 +1,235 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+
 use axum::{Extension, Json};
-use axum::extract::State;
+use axum::extract::{ConnectInfo, State};
+use axum::http::header::USER_AGENT;
+use axum::http::HeaderMap;
 use axum::response::{IntoResponse, Redirect};
-use hex::encode;
 use ring::{
     digest,
-    hmac::{HMAC_SHA256, Key, sign},
+    hmac::{HMAC_SHA256, Key, verify},
 };
 use serde::Deserialize;
-use tower_cookies::{Cookie, Cookies};
-use tracing::log::debug;
+use serde_json::Value;
+use tower_cookies::Cookies;
+use tracing::debug;
+use utoipa::ToSchema;
 
 use crate::config::config;
-use crate::handlers::auth::{SESSION_TOKEN, UserData};
+use crate::handlers::auth::{session_cookie, UserData};
 use crate::handlers::DbPool;
-use crate::infra::Random;
 use crate::infra::services::{sessions_service, users_service};
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::user_status::UserStatus;
+
+/// Seconds an `auth_date` may lie in the future, for clocks running slightly apart.
+const ALLOWED_SKEW: i64 = 5;
+
+/// Builds the data-check-string from every received field except `hash`,
+/// sorted alphabetically and joined with line feeds.
+fn data_check_string(payload: &BTreeMap<String, Value>) -> String {
+    payload
+        .iter()
+        .filter(|(key, _)| key.as_str() != "hash")
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            Value::String(value) => format!("{}={}", key, value),
+            value => format!("{}={}", key, value),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn check_telegram_hash(payload: &BTreeMap<String, Value>, hash: &str, bot_token: &str) -> bool {
+    // Generate the secret key using SHA-256 hash of the bot token
+    let secret_key = digest::digest(&digest::SHA256, bot_token.as_bytes());
+    let key = Key::new(HMAC_SHA256, secret_key.as_ref());
+
+    let Ok(signature) = hex::decode(hash) else {
+        return false;
+    };
 
-async fn verify_telegram_hash(
-    telegram_response: TelegramLoginResponse,
+    // Constant-time comparison of the received hash with the expected one
+    verify(&key, data_check_string(payload).as_bytes(), &signature).is_ok()
+}
+
+/// Whether a payload signed `age` seconds ago is recent enough, and not from the future.
+fn is_fresh(age: i64, max_age: i64) -> bool {
+    (-ALLOWED_SKEW..=max_age).contains(&age)
+}
+
+async fn verify_telegram_login(
+    payload: &BTreeMap<String, Value>,
+    telegram_response: &TelegramLoginResponse,
 ) -> Result<(), HandlerError> {
     let config = config().await;
 
-    // Generate the secret key using SHA-256 hash of the bot token
-    let secret_key = digest::digest(&digest::SHA256, config.bot_token().as_ref())
-        .as_ref()
-        .to_owned();
-
-    // String to be signed
-    let mut data_check_string = String::new();
-    let fields = vec![
-        ("auth_date", telegram_response.auth_date.to_string()),
-        ("first_name", telegram_response.first_name.clone()),
-        ("id", telegram_response.id.to_string()),
-        ("last_name", telegram_response.last_name.clone()),
-        ("photo_url", telegram_response.photo_url.clone()),
-        ("username", telegram_response.username.clone()),
-    ];
-
-    // Fill the string with values from telegram_response
-    for (key, value) in fields {
-        if key == "username" {
-            data_check_string.push_str(&format!("{}={}", key, value));
-        } else {
-            data_check_string.push_str(&format!("{}={}\n", key, value));
-        }
+    if !check_telegram_hash(payload, &telegram_response.hash, config.bot_token()) {
+        return Err(HandlerError::TelegramHashProblem);
     }
 
-    let key = Key::new(HMAC_SHA256, secret_key.as_slice());
-    let signature_value = sign(&key, data_check_string.as_ref());
-
-    if encode(signature_value) != telegram_response.hash {
-        Err(HandlerError::TelegramHashProblem)
-    } else {
-        Ok(())
+    // Reject outdated payloads so they can't be replayed, and ones dated in the future
+    let age = chrono::Utc::now().timestamp() - telegram_response.auth_date;
+    if !is_fresh(age, config.telegram_auth_max_age()) {
+        return Err(HandlerError::TelegramAuthExpired);
     }
+
+    Ok(())
 }
 
-#[derive(Deserialize)]
+/// Telegram omits `last_name`, `photo_url` and `username` when the user hasn't set them.
+#[derive(Deserialize, ToSchema)]
 pub struct TelegramLoginResponse {
-    auth_date: i32,
+    auth_date: i64,
     first_name: String,
     hash: String,
-    id: i32,
-    last_name: String,
-    photo_url: String,
-    username: String,
-}
-
-impl Clone for TelegramLoginResponse {
-    fn clone(&self) -> Self {
-        Self {
-            auth_date: self.auth_date,
-            first_name: self.first_name.clone(),
-            hash: self.hash.clone(),
-            id: self.id,
-            last_name: self.last_name.clone(),
-            photo_url: self.photo_url.clone(),
-            username: self.username.clone(),
-        }
-    }
+    id: i64,
+    last_name: Option<String>,
+    photo_url: Option<String>,
+    username: Option<String>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/login",
+    tag = "auth",
+    summary = "Log in with the Telegram login widget data",
+    request_body = TelegramLoginResponse,
+    responses(
+        (status = 303, description = "Logged in, the session cookie is set"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+)]
 pub async fn login(
     cookies: Cookies,
+    headers: HeaderMap,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
     Extension(user_data): Extension<Option<UserData>>,
-    Extension(random): Extension<Random>,
     State(pool): State<DbPool>,
-    Json(login_res): Json<TelegramLoginResponse>,
+    Json(payload): Json<BTreeMap<String, Value>>,
 ) -> Result<impl IntoResponse, HandlerError> {
     debug!("->> {:<12} - login", "HANDLER");
 
-    // create new user if not exist
-    let user_id = users_service::insert_if_not_exists(&pool, login_res.id.clone()).await?;
+    let login_res = serde_json::to_value(&payload)
+        .and_then(serde_json::from_value::<TelegramLoginResponse>)
+        .map_err(|_| HandlerError::TelegramHashProblem)?;
 
     // check if already authenticated
     if user_data.is_some() {
         return Ok(Redirect::to("/"));
     }
 
-    verify_telegram_hash(login_res.clone()).await?;
+    verify_telegram_login(&payload, &login_res).await?;
+
+    // create new user if not exist, refresh the profile otherwise
+    let new_user = users_service::NewUserDb {
+        telegram_id: login_res.id,
+        first_name: Option::from(login_res.first_name),
+        last_name: login_res.last_name,
+        username: login_res.username,
+        photo_url: login_res.photo_url,
+    };
+
+    let user_id = users_service::insert_or_update(&pool, new_user).await?;
+
+    if users_service::get(&pool, user_id).await?.status == UserStatus::Blocked {
+        return Err(HandlerError::UserBlocked);
+    }
+
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from);
 
-    let session_token = sessions_service::new_session(&pool, user_id, random)
-        .await
-        .map_err(HandlerError::CarSharingError)?;
+    let session_token = sessions_service::new_session(
+        &pool,
+        user_id,
+        user_agent,
+        Option::from(address.ip().to_string()),
+    )
+    .await
+    .map_err(HandlerError::CarSharingError)?;
 
     let cookie_session = session_token.into_cookie_value();
 
-    let mut cookie = Cookie::new(SESSION_TOKEN, cookie_session);
+    cookies.add(session_cookie(cookie_session).await);
 
-    cookie.set_http_only(true);
-    cookie.set_path("/");
-    cookie.set_secure(true);
-    cookies.add(cookie);
+    Ok(Redirect::to("/api/v1"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ring::hmac::sign;
+
+    use super::*;
+
+    const BOT_TOKEN: &str = "123456:test-bot-token";
+
+    fn payload(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn sign_payload(payload: &BTreeMap<String, Value>) -> String {
+        let secret_key = digest::digest(&digest::SHA256, BOT_TOKEN.as_bytes());
+        let key = Key::new(HMAC_SHA256, secret_key.as_ref());
+
+        hex::encode(sign(&key, data_check_string(payload).as_bytes()))
+    }
+
+    #[test]
+    fn test_data_check_string_is_sorted_and_skips_hash() {
+        let payload = payload(serde_json::json!({
+            "username": "user",
+            "id": 42,
+            "hash": "abc",
+            "auth_date": 1700000000,
+            "first_name": "First",
+        }));
+
+        assert_eq!(
+            data_check_string(&payload),
+            "auth_date=1700000000\nfirst_name=First\nid=42\nusername=user"
+        );
+    }
+
+    #[test]
+    fn test_check_telegram_hash_without_optional_fields() {
+        let payload = payload(serde_json::json!({
+            "auth_date": 1700000000,
+            "first_name": "First",
+            "id": 42,
+        }));
+
+        let hash = sign_payload(&payload);
+
+        assert!(check_telegram_hash(&payload, &hash, BOT_TOKEN));
+    }
 
-    Ok(Redirect::to("/api"))
+    #[test]
+    fn test_check_telegram_hash_rejects_tampered_payload() {
+        let mut payload = payload(serde_json::json!({
+            "auth_date": 1700000000,
+            "first_name": "First",
+            "id": 42,
+        }));
+
+        let hash = sign_payload(&payload);
+        payload.insert("id".to_string(), Value::from(43));
+
+        assert!(!check_telegram_hash(&payload, &hash, BOT_TOKEN));
+        assert!(!check_telegram_hash(&payload, "not-hex", BOT_TOKEN));
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        assert!(is_fresh(0, 86400));
+        assert!(is_fresh(86400, 86400));
+        assert!(is_fresh(-ALLOWED_SKEW, 86400));
+        assert!(!is_fresh(86401, 86400));
+        assert!(!is_fresh(-ALLOWED_SKEW - 1, 86400));
+        assert!(!is_fresh(-3600, 86400));
+    }
 }
@@ -1,24 +1,35 @@
 use axum::extract::State;
 use axum::response::{IntoResponse, Redirect};
-use tower_cookies::{Cookie, Cookies};
+use tower_cookies::Cookies;
 
-use crate::handlers::auth::SESSION_TOKEN;
+use crate::handlers::auth::{removal_session_cookie, SESSION_TOKEN};
 use crate::handlers::DbPool;
 use crate::infra::services::sessions_service;
 use crate::models::HandlerError;
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/logout",
+    tag = "auth",
+    summary = "Log out and remove the session",
+    responses(
+        (status = 303, description = "Logged out"),
+    ),
+)]
 pub async fn logout(
     cookies: Cookies,
     State(pool): State<DbPool>,
 ) -> Result<impl IntoResponse, HandlerError> {
     let session_token = cookies.get(SESSION_TOKEN).map(|c| c.value().to_string());
 
+    // Removed first, so the cookie is cleared even when deleting the session fails
+    cookies.remove(removal_session_cookie().await);
+
     if let Some(session_token) = session_token {
         sessions_service::delete_session(&pool, session_token)
             .await
             .map_err(HandlerError::CarSharingError)?;
     }
 
-    cookies.remove(Cookie::from(Cookie::build(SESSION_TOKEN).path("/")));
     Ok(Redirect::to("/"))
 }
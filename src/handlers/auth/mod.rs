@@ -1,12 +1,62 @@
+use tower_cookies::Cookie;
 use uuid::Uuid;
 
+use crate::config::config;
+use crate::models::permission::Permission;
+use crate::models::role::Role;
+use crate::models::user_status::UserStatus;
+
 pub mod login;
 pub mod logout;
 
 #[derive(Clone, Debug)]
 pub struct UserData {
-    pub telegram_id: i32,
+    #[allow(dead_code)]
+    pub telegram_id: i64,
     pub user_id: Uuid,
+    /// Set when authenticated by a session cookie.
+    pub session_id: Option<Uuid>,
+    /// Set when authenticated by an API token.
+    pub api_token_id: Option<Uuid>,
+    /// Set when authenticated by an API token, limiting the role's permissions.
+    pub scopes: Option<Vec<Permission>>,
+    pub role: Role,
+    pub status: UserStatus,
+}
+
+impl UserData {
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.role.has_permission(permission) && self.has_scope(permission)
+    }
+
+    /// Whether the API token used, if any, is scoped for the permission.
+    pub fn has_scope(&self, permission: Permission) -> bool {
+        self.scopes
+            .as_ref()
+            .is_none_or(|scopes| scopes.contains(&permission))
+    }
 }
 
 pub const SESSION_TOKEN: &str = "session-token";
+
+/// Session cookie with the attributes from the configuration.
+pub async fn session_cookie(value: String) -> Cookie<'static> {
+    let config = config().await;
+
+    let mut cookie = Cookie::new(SESSION_TOKEN, value);
+
+    cookie.set_http_only(true);
+    cookie.set_path("/");
+    cookie.set_secure(config.cookie_secure());
+    cookie.set_same_site(config.cookie_same_site());
+    if let Some(domain) = config.cookie_domain() {
+        cookie.set_domain(domain.to_string());
+    }
+
+    cookie
+}
+
+/// Cookie matching the session cookie's path and domain, used to remove it.
+pub async fn removal_session_cookie() -> Cookie<'static> {
+    session_cookie(String::new()).await
+}
@@ -1,26 +1,33 @@
 use axum::{extract::State, Json};
-use tracing::log::debug;
+use tracing::debug;
 
 use crate::handlers::cars::{CarResponse, CreateCarRequest};
 use crate::handlers::DbPool;
+use crate::handlers::validation::ValidJson;
 use crate::infra::services::cars_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/cars",
+    tag = "cars",
+    summary = "Create a car",
+    request_body = CreateCarRequest,
+    responses(
+        (status = 200, description = "Created car", body = CarResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn create_car(
     State(pool): State<DbPool>,
-    Json(new_car): Json<CreateCarRequest>,
+    ValidJson(new_car): ValidJson<CreateCarRequest>,
 ) -> Result<Json<CarResponse>, HandlerError> {
     debug!("->> {:<12} - create_car", "HANDLER");
 
-    let new_car_db = cars_service::NewCarDb {
-        name: new_car.name,
-        hourly_rate: new_car.hourly_rate,
-        daily_rate: new_car.daily_rate,
-        weekly_rate: new_car.weekly_rate,
-        photos: new_car.photos,
-    };
-
-    let created_car = cars_service::insert(&pool, new_car_db).await?;
+    let created_car = cars_service::insert(&pool, new_car.into()).await?;
 
     Ok(Json(created_car))
 }
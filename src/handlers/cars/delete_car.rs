@@ -1,11 +1,26 @@
 use axum::extract::{Path, State};
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::handlers::DbPool;
 use crate::infra::services::cars_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/cars/{id}",
+    tag = "cars",
+    summary = "Delete a car",
+    params(("id" = Uuid, Path, description = "Car id")),
+    responses(
+        (status = 200, description = "Car deleted", body = String),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Conflicts with existing data", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn delete_car(
     State(pool): State<DbPool>,
     Path(id): Path<Uuid>,
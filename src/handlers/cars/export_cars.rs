@@ -0,0 +1,52 @@
+use axum::extract::{Query, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::IntoResponse;
+use tracing::debug;
+
+use crate::handlers::cars::{CarResponse, ExportCarsParams};
+use crate::handlers::cars::fleet::{self, FleetFormat};
+use crate::handlers::DbPool;
+use crate::infra::services::{cars_service, cars_service::CarsFilter};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/cars/export",
+    tag = "cars",
+    summary = "Export every car",
+    description = "The file can be imported back as is, though cars without a license plate \
+                   are then created again instead of updated.",
+    params(ExportCarsParams),
+    responses(
+        (status = 200, description = "Cars", content(
+            (Vec<CarResponse> = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn export_cars(
+    State(pool): State<DbPool>,
+    Query(params): Query<ExportCarsParams>,
+) -> Result<impl IntoResponse, HandlerError> {
+    debug!("->> {:<12} - export_cars", "HANDLER");
+
+    let format = params.format.unwrap_or(FleetFormat::Json);
+
+    let cars = cars_service::get_all(&pool, CarsFilter::default())
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok((
+        [
+            (CONTENT_TYPE, format.content_type().to_string()),
+            (
+                CONTENT_DISPOSITION,
+                format!("attachment; filename=\"cars.{}\"", format.extension()),
+            ),
+        ],
+        fleet::write_fleet(format, &cars),
+    ))
+}
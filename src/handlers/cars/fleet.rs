@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::handlers::cars::{
+    check_license_plate, check_name, check_rates, check_status, CarResponse,
+};
+use crate::handlers::validation::Validate;
+use crate::infra::csv;
+use crate::infra::services::cars_service::{ImportCarDb, NewCarDb};
+use crate::models::FieldError;
+
+/// Columns written by `write_fleet`, `created_at` is ignored when importing.
+const CSV_COLUMNS: [&str; 9] = [
+    "id",
+    "license_plate",
+    "name",
+    "hourly_rate",
+    "daily_rate",
+    "weekly_rate",
+    "photos",
+    "status",
+    "created_at",
+];
+
+const IGNORED_FIELDS: [&str; 1] = ["created_at"];
+
+/// Photos share a single CSV cell.
+const PHOTOS_SEPARATOR: char = '|';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FleetFormat {
+    Json,
+    Csv,
+}
+
+impl FleetFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FleetFormat::Json => "application/json",
+            FleetFormat::Csv => "text/csv; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FleetFormat::Json => "json",
+            FleetFormat::Csv => "csv",
+        }
+    }
+
+    pub fn from_content_type(content_type: &str) -> Option<FleetFormat> {
+        match content_type.split(';').next()?.trim() {
+            "application/json" => Some(FleetFormat::Json),
+            "text/csv" => Some(FleetFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// Files are JSON unless they end with `.csv`.
+    pub fn from_path(path: &Path) -> FleetFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => FleetFormat::Csv,
+            _ => FleetFormat::Json,
+        }
+    }
+}
+
+impl FromStr for FleetFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(FleetFormat::Json),
+            "csv" => Ok(FleetFormat::Csv),
+            _ => Err(format!("`{}` is not a fleet format, expected json or csv", s)),
+        }
+    }
+}
+
+/// A car as listed in an imported file, matched to the stored cars by id, or by license
+/// plate when it has no id. Cars with neither can't be matched, so they're created anew.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CarRecord {
+    /// Must be the id of a stored car, e.g. as exported.
+    #[serde(default)]
+    id: Option<Uuid>,
+    #[serde(default)]
+    license_plate: Option<String>,
+    name: String,
+    hourly_rate: i32,
+    daily_rate: i32,
+    weekly_rate: i32,
+    #[serde(default)]
+    photos: Option<Vec<Option<String>>>,
+    /// Kept as is on existing cars when unset.
+    #[serde(default)]
+    status: Option<String>,
+}
+
+impl Validate for CarRecord {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if let Some(license_plate) = &self.license_plate {
+            check_license_plate(&mut errors, license_plate);
+        }
+        check_name(&mut errors, &self.name);
+        check_rates(&mut errors, self.hourly_rate, self.daily_rate, self.weekly_rate);
+        if let Some(status) = &self.status {
+            check_status(&mut errors, status);
+        }
+
+        errors
+    }
+}
+
+impl From<CarRecord> for ImportCarDb {
+    fn from(record: CarRecord) -> Self {
+        ImportCarDb {
+            id: record.id,
+            new_car: NewCarDb {
+                name: record.name,
+                hourly_rate: record.hourly_rate,
+                daily_rate: record.daily_rate,
+                weekly_rate: record.weekly_rate,
+                photos: record.photos,
+                license_plate: record.license_plate,
+                status: record.status,
+            },
+        }
+    }
+}
+
+/// Reads and validates every row of an imported file, so the errors of all rows are
+/// reported at once. Nothing is returned unless every row is valid.
+pub fn read_fleet(format: FleetFormat, content: &str) -> Result<Vec<ImportCarDb>, Vec<FieldError>> {
+    let rows = match format {
+        FleetFormat::Json => read_json(content),
+        FleetFormat::Csv => read_csv(content),
+    }
+    .map_err(|error| vec![error])?;
+
+    let mut errors = Vec::new();
+    let mut records = Vec::new();
+    let mut ids = HashMap::new();
+    let mut plates = HashMap::new();
+
+    for (index, row) in rows.into_iter().enumerate() {
+        let row_field = format!("rows[{}]", index + 1);
+
+        let record = match row {
+            Ok(record) => record,
+            Err(row_errors) => {
+                errors.extend(row_errors.into_iter().map(|error| in_row(&row_field, error)));
+                continue;
+            }
+        };
+
+        let row_errors = record.validate();
+        if !row_errors.is_empty() {
+            errors.extend(row_errors.into_iter().map(|error| in_row(&row_field, error)));
+            continue;
+        }
+
+        if let Some(id) = record.id {
+            if let Some(first_row) = ids.insert(id, index + 1) {
+                errors.push(FieldError::new(
+                    &format!("{}.id", row_field),
+                    &format!("is already used by rows[{}]", first_row),
+                ));
+            }
+        }
+
+        if let Some(license_plate) = &record.license_plate {
+            if let Some(first_row) = plates.insert(license_plate.clone(), index + 1) {
+                errors.push(FieldError::new(
+                    &format!("{}.license_plate", row_field),
+                    &format!("is already used by rows[{}]", first_row),
+                ));
+            }
+        }
+
+        records.push(record);
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(records.into_iter().map(ImportCarDb::from).collect())
+}
+
+fn in_row(row_field: &str, error: FieldError) -> FieldError {
+    if error.field.is_empty() {
+        FieldError::new(row_field, &error.message)
+    } else {
+        FieldError::new(&format!("{}.{}", row_field, error.field), &error.message)
+    }
+}
+
+fn read_json(content: &str) -> Result<Vec<Result<CarRecord, Vec<FieldError>>>, FieldError> {
+    let rows = serde_json::from_str::<Vec<serde_json::Value>>(content)
+        .map_err(|err| FieldError::new("body", &err.to_string()))?;
+
+    let records = rows
+        .into_iter()
+        .map(|mut row| {
+            if let Some(fields) = row.as_object_mut() {
+                for field in IGNORED_FIELDS {
+                    fields.remove(field);
+                }
+            }
+
+            serde_json::from_value::<CarRecord>(row)
+                .map_err(|err| vec![FieldError::new("", &err.to_string())])
+        })
+        .collect();
+
+    Ok(records)
+}
+
+fn read_csv(content: &str) -> Result<Vec<Result<CarRecord, Vec<FieldError>>>, FieldError> {
+    let mut rows = csv::parse(content)
+        .map_err(|err| FieldError::new("body", &err))?
+        .into_iter();
+
+    let header = rows
+        .next()
+        .ok_or_else(|| FieldError::new("body", "the header row is missing"))?;
+
+    if let Some(column) = header.iter().find(|column| !CSV_COLUMNS.contains(&column.as_str())) {
+        return Err(FieldError::new("header", &format!("unknown column `{}`", column)));
+    }
+
+    Ok(rows.map(|row| read_csv_row(&header, &row)).collect())
+}
+
+fn read_csv_row(header: &[String], row: &[String]) -> Result<CarRecord, Vec<FieldError>> {
+    if row.len() != header.len() {
+        return Err(vec![FieldError::new(
+            "",
+            &format!("has {} fields but the header has {}", row.len(), header.len()),
+        )]);
+    }
+
+    // Empty cells count as unset
+    let cell = |column: &str| {
+        header
+            .iter()
+            .position(|name| name == column)
+            .map(|index| row[index].as_str())
+            .filter(|value| !value.is_empty())
+    };
+
+    let mut errors = Vec::new();
+    let mut rate = |column: &str| match cell(column).map(str::parse::<i32>) {
+        Some(Ok(rate)) => rate,
+        Some(Err(_)) => {
+            errors.push(FieldError::new(column, "must be a whole number"));
+            0
+        }
+        None => {
+            errors.push(FieldError::new(column, "must be set"));
+            0
+        }
+    };
+
+    let hourly_rate = rate("hourly_rate");
+    let daily_rate = rate("daily_rate");
+    let weekly_rate = rate("weekly_rate");
+
+    let id = match cell("id").map(Uuid::parse_str) {
+        Some(Ok(id)) => Some(id),
+        Some(Err(_)) => {
+            errors.push(FieldError::new("id", "must be a UUID"));
+            None
+        }
+        None => None,
+    };
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(CarRecord {
+        id,
+        license_plate: cell("license_plate").map(str::to_string),
+        name: cell("name").unwrap_or_default().to_string(),
+        hourly_rate,
+        daily_rate,
+        weekly_rate,
+        photos: cell("photos").map(|photos| {
+            photos
+                .split(PHOTOS_SEPARATOR)
+                .map(|photo| Some(photo.to_string()))
+                .collect()
+        }),
+        status: cell("status").map(str::to_string),
+    })
+}
+
+/// Writes the cars with all of their fields, in a form `read_fleet` accepts back, their
+/// ids matching the same cars again.
+pub fn write_fleet(format: FleetFormat, cars: &[CarResponse]) -> String {
+    match format {
+        FleetFormat::Json => {
+            serde_json::to_string_pretty(cars).expect("cars are always serializable")
+        }
+        FleetFormat::Csv => {
+            let mut out = String::new();
+            csv::write_row(&mut out, &CSV_COLUMNS);
+
+            for car in cars {
+                let photos = car
+                    .photos
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(&PHOTOS_SEPARATOR.to_string());
+
+                csv::write_row(
+                    &mut out,
+                    &[
+                        car.id.to_string(),
+                        car.license_plate.clone().unwrap_or_default(),
+                        car.name.clone(),
+                        car.hourly_rate.to_string(),
+                        car.daily_rate.to_string(),
+                        car.weekly_rate.to_string(),
+                        photos,
+                        car.status.clone(),
+                        car.created_at.format("%Y-%m-%dT%H:%M:%S%.f").to_string(),
+                    ],
+                );
+            }
+
+            out
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn invalid_fields(format: FleetFormat, content: &str) -> Vec<String> {
+        read_fleet(format, content)
+            .err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|error| error.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_read_fleet_errors() {
+        let csv = "license_plate,name,hourly_rate,daily_rate,weekly_rate\n\
+                   AB123,Car,20,150,800\n\
+                   ,Car,20,150,800\n\
+                   AB124,Car,twenty,150,800\n\
+                   AB123,Car,20,150,1051\n\
+                   AB123,Car,20,150,800\n\
+                   AB125,Car\n";
+
+        assert_eq!(
+            invalid_fields(FleetFormat::Csv, csv),
+            vec![
+                "rows[3].hourly_rate",
+                "rows[4].weekly_rate",
+                "rows[5].license_plate",
+                "rows[6]",
+            ]
+        );
+        assert_eq!(invalid_fields(FleetFormat::Csv, "plate\nAB123\n"), vec!["header"]);
+        assert_eq!(
+            invalid_fields(
+                FleetFormat::Csv,
+                "id,name,hourly_rate,daily_rate,weekly_rate\n\
+                 42,Car,20,150,800\n\
+                 0b8a8f5e-3f3c-4d6e-9a51-6c0e1d6f2a10,Car,20,150,800\n\
+                 0b8a8f5e-3f3c-4d6e-9a51-6c0e1d6f2a10,Car,20,150,800\n"
+            ),
+            vec!["rows[1].id", "rows[3].id"]
+        );
+        assert_eq!(
+            invalid_fields(FleetFormat::Json, r#"[{"name": "Car"}, {"license_plate": "AB123"}]"#),
+            vec!["rows[1]", "rows[2]"]
+        );
+    }
+
+    #[test]
+    fn test_export_imports_back() {
+        let cars = vec![
+            CarResponse {
+                id: Uuid::new_v4(),
+                license_plate: Some("AB123".to_string()),
+                name: "Car, \"red\"".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: Some(vec![Some("a.jpg".to_string()), Some("b.jpg".to_string())]),
+                status: "available".to_string(),
+                created_at: NaiveDateTime::default(),
+            },
+            CarResponse {
+                id: Uuid::new_v4(),
+                license_plate: None,
+                name: "Unregistered".to_string(),
+                hourly_rate: 10,
+                daily_rate: 60,
+                weekly_rate: 300,
+                photos: None,
+                status: "in_maintenance".to_string(),
+                created_at: NaiveDateTime::default(),
+            },
+        ];
+
+        for format in [FleetFormat::Json, FleetFormat::Csv] {
+            let new_cars = read_fleet(format, &write_fleet(format, &cars)).unwrap();
+
+            assert_eq!(new_cars.len(), 2);
+            assert_eq!(new_cars[0].id, Some(cars[0].id));
+            assert_eq!(new_cars[0].new_car.license_plate.as_deref(), Some("AB123"));
+            assert_eq!(new_cars[0].new_car.name, "Car, \"red\"");
+            assert_eq!(new_cars[0].new_car.photos.as_ref().map(Vec::len), Some(2));
+            assert_eq!(new_cars[0].new_car.status.as_deref(), Some("available"));
+            assert_eq!(new_cars[1].id, Some(cars[1].id));
+            assert_eq!(new_cars[1].new_car.license_plate, None);
+            assert_eq!(new_cars[1].new_car.name, "Unregistered");
+            assert_eq!(new_cars[1].new_car.photos, None);
+        }
+    }
+}
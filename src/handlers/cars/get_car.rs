@@ -1,13 +1,27 @@
 use axum::extract::{Path, State};
 use axum::Json;
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::handlers::cars::CarResponse;
 use crate::handlers::DbPool;
 use crate::infra::services::cars_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/cars/{id}",
+    tag = "cars",
+    summary = "Get a car",
+    params(("id" = Uuid, Path, description = "Car id")),
+    responses(
+        (status = 200, description = "Car", body = CarResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn get_car(
     State(pool): State<DbPool>,
     Path(id): Path<Uuid>,
@@ -0,0 +1,72 @@
+use axum::extract::{Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::HeaderMap;
+use axum::Json;
+use tracing::debug;
+
+use crate::handlers::cars::{ImportCarsParams, ImportReport};
+use crate::handlers::cars::fleet::{self, CarRecord, FleetFormat};
+use crate::handlers::DbPool;
+use crate::infra::services::cars_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/cars/import",
+    tag = "cars",
+    summary = "Import cars",
+    description = "Updates the cars with the listed ids or, for rows without one, the same \
+                   license plate, and creates the rows matching neither. Either every row is saved \
+                   or none.",
+    params(ImportCarsParams),
+    request_body(
+        content(
+            (Vec<CarRecord> = "application/json"),
+            (String = "text/csv", example = "license_plate,name,hourly_rate,daily_rate,weekly_rate\nAB123CD,Car,20,150,800"),
+        ),
+    ),
+    responses(
+        (status = 200, description = "Imported or, on dry runs, checked cars", body = ImportReport),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid rows", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn import_cars(
+    State(pool): State<DbPool>,
+    Query(params): Query<ImportCarsParams>,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<ImportReport>, HandlerError> {
+    debug!("->> {:<12} - import_cars", "HANDLER");
+
+    let format = params
+        .format
+        .or_else(|| {
+            headers
+                .get(CONTENT_TYPE)
+                .and_then(|content_type| content_type.to_str().ok())
+                .and_then(FleetFormat::from_content_type)
+        })
+        .unwrap_or(FleetFormat::Json);
+
+    let report = match fleet::read_fleet(format, &body) {
+        Ok(rows) => cars_service::import(&pool, rows, params.dry_run)
+            .await
+            .map_err(HandlerError::CarSharingError)?,
+        Err(errors) if params.dry_run => ImportReport {
+            dry_run: true,
+            created: 0,
+            updated: 0,
+            errors,
+        },
+        Err(errors) => return Err(HandlerError::ValidationError(errors)),
+    };
+
+    if !report.dry_run && !report.errors.is_empty() {
+        return Err(HandlerError::ValidationError(report.errors));
+    }
+
+    Ok(Json(report))
+}
@@ -1,13 +1,26 @@
 use axum::extract::{Query, State};
 use axum::Json;
-use tracing::log::debug;
+use tracing::debug;
 
 use crate::handlers::cars::CarResponse;
 use crate::handlers::DbPool;
 use crate::infra::services::{cars_service, cars_service::CarsFilter};
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 use crate::models::HandlerError::CarSharingError;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/cars",
+    tag = "cars",
+    summary = "List cars",
+    params(CarsFilter),
+    responses(
+        (status = 200, description = "Cars", body = Vec<CarResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn list_cars(
     State(pool): State<DbPool>,
     Query(params): Query<CarsFilter>,
@@ -16,7 +29,7 @@ pub async fn list_cars(
 
     let cars = cars_service::get_all(&pool, params)
         .await
-        .map_err(|err| CarSharingError(err))?;
+        .map_err(CarSharingError)?;
 
     Ok(Json(cars))
 }
@@ -1,18 +1,26 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
-use crate::infra::services::cars_service::CarDb;
+use crate::handlers::cars::fleet::FleetFormat;
+use crate::handlers::validation::{check_max_length, check_not_blank, check_positive, Validate};
+use crate::infra::services::cars_service::{CarDb, NewCarDb};
+use crate::models::FieldError;
 
 pub mod create_car;
 pub mod delete_car;
+pub mod export_cars;
+pub mod fleet;
 pub mod get_car;
+pub mod import_cars;
 pub mod list_cars;
 pub mod update_car;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CarResponse {
     pub id: Uuid,
+    pub license_plate: Option<String>,
     pub name: String,
     pub hourly_rate: i32,
     pub daily_rate: i32,
@@ -26,6 +34,7 @@ impl From<CarDb> for CarResponse {
     fn from(car_db: CarDb) -> Self {
         CarResponse {
             id: car_db.id,
+            license_plate: car_db.license_plate,
             name: car_db.name,
             hourly_rate: car_db.hourly_rate,
             daily_rate: car_db.daily_rate,
@@ -37,19 +46,181 @@ impl From<CarDb> for CarResponse {
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CreateCarRequest {
+    license_plate: Option<String>,
     name: String,
     hourly_rate: i32,
     daily_rate: i32,
     weekly_rate: i32,
     photos: Option<Vec<Option<String>>>,
 }
-#[derive(Debug, Deserialize)]
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 pub struct UpdateCarRequest {
     pub name: Option<String>,
     pub hourly_rate: Option<i32>,
     pub daily_rate: Option<i32>,
     pub weekly_rate: Option<i32>,
     pub status: Option<String>,
+    pub license_plate: Option<String>,
+}
+
+impl UpdateCarRequest {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.hourly_rate.is_none()
+            && self.daily_rate.is_none()
+            && self.weekly_rate.is_none()
+            && self.status.is_none()
+            && self.license_plate.is_none()
+    }
+}
+
+impl From<CreateCarRequest> for NewCarDb {
+    fn from(new_car: CreateCarRequest) -> Self {
+        NewCarDb {
+            name: new_car.name,
+            hourly_rate: new_car.hourly_rate,
+            daily_rate: new_car.daily_rate,
+            weekly_rate: new_car.weekly_rate,
+            photos: new_car.photos,
+            license_plate: new_car.license_plate,
+            status: None,
+        }
+    }
+}
+
+impl Validate for CreateCarRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if let Some(license_plate) = &self.license_plate {
+            check_license_plate(&mut errors, license_plate);
+        }
+        check_name(&mut errors, &self.name);
+        check_rates(&mut errors, self.hourly_rate, self.daily_rate, self.weekly_rate);
+
+        errors
+    }
+}
+
+/// Rates are checked against each other in `update_car`, once merged with the stored ones.
+impl Validate for UpdateCarRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.is_empty() {
+            errors.push(FieldError::new("body", "at least one field must be set"));
+        }
+        if let Some(name) = &self.name {
+            check_name(&mut errors, name);
+        }
+        if let Some(status) = &self.status {
+            check_status(&mut errors, status);
+        }
+        if let Some(license_plate) = &self.license_plate {
+            check_license_plate(&mut errors, license_plate);
+        }
+
+        errors
+    }
+}
+
+/// Query of `import_cars`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ImportCarsParams {
+    /// Format of the body, taken from its content type when unset.
+    pub format: Option<FleetFormat>,
+    /// Only validate the rows and count what would change.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Query of `export_cars`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ExportCarsParams {
+    /// Defaults to JSON.
+    pub format: Option<FleetFormat>,
+}
+
+/// Outcome of an import. Invalid rows are only reported by dry runs, a real import
+/// with invalid rows is rejected as a whole.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub created: usize,
+    pub updated: usize,
+    /// Rows are numbered from 1, without counting the CSV header, e.g. `rows[2].name`.
+    pub errors: Vec<FieldError>,
+}
+
+fn check_name(errors: &mut Vec<FieldError>, name: &str) {
+    check_not_blank(errors, "name", name);
+    check_max_length(errors, "name", name, 50);
+}
+
+fn check_status(errors: &mut Vec<FieldError>, status: &str) {
+    check_not_blank(errors, "status", status);
+    check_max_length(errors, "status", status, 30);
+}
+
+fn check_license_plate(errors: &mut Vec<FieldError>, license_plate: &str) {
+    check_not_blank(errors, "license_plate", license_plate);
+    check_max_length(errors, "license_plate", license_plate, 20);
+}
+
+/// Longer rentals must never cost more than paying the shorter rate for the same time.
+pub fn check_rates(errors: &mut Vec<FieldError>, hourly_rate: i32, daily_rate: i32, weekly_rate: i32) {
+    check_positive(errors, "hourly_rate", hourly_rate);
+    check_positive(errors, "daily_rate", daily_rate);
+    check_positive(errors, "weekly_rate", weekly_rate);
+
+    if i64::from(daily_rate) > 24 * i64::from(hourly_rate) {
+        errors.push(FieldError::new("daily_rate", "must not exceed 24 hourly rates"));
+    }
+    if i64::from(weekly_rate) > 7 * i64::from(daily_rate) {
+        errors.push(FieldError::new("weekly_rate", "must not exceed 7 daily rates"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_car_request(name: &str, hourly_rate: i32, daily_rate: i32, weekly_rate: i32) -> CreateCarRequest {
+        CreateCarRequest {
+            license_plate: None,
+            name: name.to_string(),
+            hourly_rate,
+            daily_rate,
+            weekly_rate,
+            photos: None,
+        }
+    }
+
+    fn invalid_fields(request: CreateCarRequest) -> Vec<String> {
+        request.validate().into_iter().map(|e| e.field).collect()
+    }
+
+    #[test]
+    fn test_valid_car() {
+        assert!(invalid_fields(create_car_request("My Awesome Car", 20, 150, 800)).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_car() {
+        assert_eq!(
+            invalid_fields(create_car_request(&"a".repeat(51), 0, 150, 800)),
+            vec!["name", "hourly_rate", "daily_rate"]
+        );
+        assert_eq!(
+            invalid_fields(create_car_request("Car", 20, 100, 701)),
+            vec!["weekly_rate"]
+        );
+    }
 }
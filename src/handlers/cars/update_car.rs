@@ -1,20 +1,59 @@
 use axum::extract::{Path, State};
 use axum::Json;
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
-use crate::handlers::cars::{CarResponse, UpdateCarRequest};
+use crate::handlers::cars::{check_rates, CarResponse, UpdateCarRequest};
 use crate::handlers::DbPool;
+use crate::handlers::validation::ValidJson;
 use crate::infra::services::cars_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    patch,
+    path = "/api/v1/cars/{id}",
+    tag = "cars",
+    summary = "Update a car",
+    request_body = UpdateCarRequest,
+    params(("id" = Uuid, Path, description = "Car id")),
+    responses(
+        (status = 200, description = "Updated car", body = CarResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn update_car(
     State(pool): State<DbPool>,
     Path(id): Path<Uuid>,
-    Json(updated_car): Json<UpdateCarRequest>,
+    ValidJson(updated_car): ValidJson<UpdateCarRequest>,
 ) -> Result<Json<CarResponse>, HandlerError> {
     debug!("->> {:<12} - update_car", "HANDLER");
 
+    // Rates not being updated still have to agree with the new ones
+    if updated_car.hourly_rate.is_some()
+        || updated_car.daily_rate.is_some()
+        || updated_car.weekly_rate.is_some()
+    {
+        let car = cars_service::get(&pool, id)
+            .await
+            .map_err(HandlerError::CarSharingError)?;
+
+        let mut errors = Vec::new();
+        check_rates(
+            &mut errors,
+            updated_car.hourly_rate.unwrap_or(car.hourly_rate),
+            updated_car.daily_rate.unwrap_or(car.daily_rate),
+            updated_car.weekly_rate.unwrap_or(car.weekly_rate),
+        );
+
+        if !errors.is_empty() {
+            return Err(HandlerError::ValidationError(errors));
+        }
+    }
+
     let car = cars_service::update(&pool, id, updated_car)
         .await
         .map_err(HandlerError::CarSharingError)?;
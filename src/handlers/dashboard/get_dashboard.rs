@@ -0,0 +1,38 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use chrono::Utc;
+use tracing::debug;
+
+use crate::handlers::dashboard::{DashboardParams, DashboardResponse, DEFAULT_LIMIT, MAX_LIMIT};
+use crate::handlers::DbPool;
+use crate::infra::services::dashboard_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/admin/dashboard",
+    tag = "dashboard",
+    summary = "Get the orders needing attention and today's revenue",
+    params(DashboardParams),
+    responses(
+        (status = 200, description = "Dashboard", body = DashboardResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_dashboard(
+    State(pool): State<DbPool>,
+    Query(params): Query<DashboardParams>,
+) -> Result<Json<DashboardResponse>, HandlerError> {
+    debug!("->> {:<12} - get_dashboard", "HANDLER");
+
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+
+    let dashboard =
+        dashboard_service::get_dashboard(&pool, Utc::now().naive_utc(), i64::from(limit))
+            .await
+            .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(dashboard))
+}
@@ -0,0 +1,84 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::infra::services::dashboard_service::DashboardOrderDb;
+
+pub mod get_dashboard;
+
+/// Orders listed per section when the query doesn't set a limit.
+pub const DEFAULT_LIMIT: u32 = 10;
+
+/// Orders listed per section at most.
+pub const MAX_LIMIT: u32 = 100;
+
+/// Query of `get_dashboard`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DashboardParams {
+    /// Orders listed per section, 10 by default and at most 100.
+    pub limit: Option<u32>,
+}
+
+/// Everything needing attention right now. Rentals due today or overdue are also counted
+/// among the rentals in progress.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DashboardResponse {
+    pub pending_confirmations: DashboardSection,
+    /// Accepted orders whose rental hasn't started yet.
+    pub awaiting_pickup: DashboardSection,
+    pub rentals_in_progress: DashboardSection,
+    pub due_today: DashboardSection,
+    pub overdue: DashboardSection,
+    pub unpaid_finished: DashboardSection,
+    pub revenue_today: DashboardRevenue,
+}
+
+/// How many orders a section holds, and the oldest of them.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DashboardSection {
+    pub count: i64,
+    pub orders: Vec<DashboardOrder>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DashboardOrder {
+    pub order_id: Uuid,
+    pub car_id: Uuid,
+    pub car_name: String,
+    pub user_id: Uuid,
+    pub status: String,
+    pub paid: bool,
+    pub created_at: NaiveDateTime,
+    pub start_rent_time: Option<NaiveDateTime>,
+    pub end_rent_time: Option<NaiveDateTime>,
+    pub due_at: Option<NaiveDateTime>,
+    /// Fixed at the car's rates once the rental is finished.
+    pub price: Option<i32>,
+}
+
+impl From<DashboardOrderDb> for DashboardOrder {
+    fn from(order_db: DashboardOrderDb) -> Self {
+        DashboardOrder {
+            order_id: order_db.order_id,
+            car_id: order_db.car_id,
+            car_name: order_db.car_name,
+            user_id: order_db.user_id,
+            status: order_db.status,
+            paid: order_db.paid,
+            created_at: order_db.created_at,
+            start_rent_time: order_db.start_rent_time,
+            end_rent_time: order_db.end_rent_time,
+            due_at: order_db.due_at,
+            price: order_db.price,
+        }
+    }
+}
+
+/// Prices of the rentals finished today (UTC).
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DashboardRevenue {
+    pub earned: i64,
+    pub paid: i64,
+}
@@ -0,0 +1,21 @@
+use axum::Json;
+use tracing::debug;
+
+use crate::handlers::health::{HealthStatus, LivenessResponse};
+
+#[utoipa::path(
+    get,
+    path = "/health/live",
+    tag = "health",
+    summary = "Check that the server is running",
+    responses(
+        (status = 200, description = "Server is running", body = LivenessResponse),
+    ),
+)]
+pub async fn get_liveness() -> Json<LivenessResponse> {
+    debug!("->> {:<12} - get_liveness", "HANDLER");
+
+    Json(LivenessResponse {
+        status: HealthStatus::Up,
+    })
+}
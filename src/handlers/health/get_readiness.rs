@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use tokio::time::timeout;
+use tracing::{debug, error};
+
+use crate::handlers::DbPool;
+use crate::handlers::health::{DependencyStatus, HealthStatus, ReadinessResponse};
+use crate::infra::services::health_service;
+
+/// Probes give up well before the pool's own connection timeout.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    tag = "health",
+    summary = "Check that the server can handle requests",
+    responses(
+        (status = 200, description = "All dependencies are up", body = ReadinessResponse),
+        (status = 503, description = "A dependency is down", body = ReadinessResponse),
+    ),
+)]
+pub async fn get_readiness(State(pool): State<DbPool>) -> (StatusCode, Json<ReadinessResponse>) {
+    debug!("->> {:<12} - get_readiness", "HANDLER");
+
+    let database = match timeout(CHECK_TIMEOUT, health_service::check_database(&pool)).await {
+        Ok(Ok(())) => DependencyStatus::up(),
+        Ok(Err(err)) => {
+            error!("->> {:<12} - database check failed: {}", "HANDLER", err);
+            DependencyStatus::down("database is unreachable")
+        }
+        Err(_) => DependencyStatus::down("database check timed out"),
+    };
+
+    let migrations = if database.status == HealthStatus::Down {
+        DependencyStatus::down("database is unreachable")
+    } else {
+        match timeout(CHECK_TIMEOUT, health_service::get_pending_migrations(&pool)).await {
+            Ok(Ok(pending)) if pending.is_empty() => DependencyStatus::up(),
+            Ok(Ok(pending)) => {
+                DependencyStatus::down(format!("pending migrations: {}", pending.join(", ")))
+            }
+            Ok(Err(err)) => {
+                error!("->> {:<12} - migrations check failed: {}", "HANDLER", err);
+                DependencyStatus::down("migrations couldn't be checked")
+            }
+            Err(_) => DependencyStatus::down("migrations check timed out"),
+        }
+    };
+
+    let status = if database.status == HealthStatus::Up && migrations.status == HealthStatus::Up {
+        HealthStatus::Up
+    } else {
+        HealthStatus::Down
+    };
+
+    let status_code = match status {
+        HealthStatus::Up => StatusCode::OK,
+        HealthStatus::Down => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (
+        status_code,
+        Json(ReadinessResponse {
+            status,
+            database,
+            migrations,
+        }),
+    )
+}
@@ -0,0 +1,49 @@
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub mod get_liveness;
+pub mod get_readiness;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct LivenessResponse {
+    pub status: HealthStatus,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DependencyStatus {
+    pub status: HealthStatus,
+    /// Why the dependency is down.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// Up only when every dependency is.
+    pub status: HealthStatus,
+    pub database: DependencyStatus,
+    pub migrations: DependencyStatus,
+}
+
+impl DependencyStatus {
+    pub fn up() -> Self {
+        DependencyStatus {
+            status: HealthStatus::Up,
+            reason: None,
+        }
+    }
+
+    pub fn down(reason: impl Into<String>) -> Self {
+        DependencyStatus {
+            status: HealthStatus::Down,
+            reason: Some(reason.into()),
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::licenses::DriverLicenseResponse;
+use crate::infra::services::driver_licenses_service;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::license_status::LicenseStatus;
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/licenses/approve/{id}",
+    tag = "licenses",
+    summary = "Approve a driver's license",
+    params(("id" = Uuid, Path, description = "Driver's license id")),
+    responses(
+        (status = 200, description = "Approved driver's license", body = DriverLicenseResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed, or the reviewer's own license", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Already reviewed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn approve_license(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(license_id): Path<Uuid>,
+) -> Result<Json<DriverLicenseResponse>, HandlerError> {
+    debug!("->> {:<12} - approve_license", "HANDLER");
+
+    let license = driver_licenses_service::review(
+        &pool,
+        license_id,
+        Option::from(user_data.user_id),
+        LicenseStatus::Approved,
+        None,
+    )
+    .await
+    .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(license))
+}
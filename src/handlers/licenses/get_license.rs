@@ -0,0 +1,36 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::DbPool;
+use crate::handlers::licenses::DriverLicenseResponse;
+use crate::infra::services::driver_licenses_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/licenses/{id}",
+    tag = "licenses",
+    summary = "Get a driver's license",
+    params(("id" = Uuid, Path, description = "Driver's license id")),
+    responses(
+        (status = 200, description = "Driver's license", body = DriverLicenseResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_license(
+    State(pool): State<DbPool>,
+    Path(license_id): Path<Uuid>,
+) -> Result<Json<DriverLicenseResponse>, HandlerError> {
+    debug!("->> {:<12} - get_license", "HANDLER");
+
+    let license = driver_licenses_service::get(&pool, license_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(license))
+}
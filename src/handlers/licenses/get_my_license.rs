@@ -0,0 +1,35 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::licenses::DriverLicenseResponse;
+use crate::infra::services::driver_licenses_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me/license",
+    tag = "licenses",
+    summary = "Get the current user's driver's license",
+    responses(
+        (status = 200, description = "Driver's license", body = DriverLicenseResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_my_license(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+) -> Result<Json<DriverLicenseResponse>, HandlerError> {
+    debug!("->> {:<12} - get_my_license", "HANDLER");
+
+    let license = driver_licenses_service::get_by_user(&pool, user_data.user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(license))
+}
@@ -0,0 +1,36 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use tracing::debug;
+
+use crate::handlers::DbPool;
+use crate::handlers::licenses::DriverLicenseResponse;
+use crate::infra::services::{
+    driver_licenses_service, driver_licenses_service::DriverLicensesFilter,
+};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/licenses",
+    tag = "licenses",
+    summary = "List driver's licenses",
+    params(DriverLicensesFilter),
+    responses(
+        (status = 200, description = "Driver's licenses", body = Vec<DriverLicenseResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn list_licenses(
+    State(pool): State<DbPool>,
+    Query(params): Query<DriverLicensesFilter>,
+) -> Result<Json<Vec<DriverLicenseResponse>>, HandlerError> {
+    debug!("->> {:<12} - list_licenses", "HANDLER");
+
+    let licenses = driver_licenses_service::get_all(&pool, params)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(licenses))
+}
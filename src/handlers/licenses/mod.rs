@@ -0,0 +1,111 @@
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::handlers::validation::{check_max_length, check_not_blank, Validate};
+use crate::infra::services::driver_licenses_service::DriverLicenseDb;
+use crate::models::FieldError;
+use crate::models::license_status::LicenseStatus;
+
+// User:
+pub mod get_my_license;
+pub mod submit_license;
+
+// Admin
+pub mod approve_license;
+pub mod get_license;
+pub mod list_licenses;
+pub mod reject_license;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DriverLicenseResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub license_number: String,
+    pub categories: Vec<String>,
+    pub issued_at: NaiveDate,
+    pub expires_at: NaiveDate,
+    pub photos: Vec<String>,
+    pub status: LicenseStatus,
+    pub rejection_reason: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<DriverLicenseDb> for DriverLicenseResponse {
+    fn from(driver_license_db: DriverLicenseDb) -> Self {
+        DriverLicenseResponse {
+            id: driver_license_db.id,
+            user_id: driver_license_db.user_id,
+            license_number: driver_license_db.license_number,
+            categories: driver_license_db.categories,
+            issued_at: driver_license_db.issued_at,
+            expires_at: driver_license_db.expires_at,
+            photos: driver_license_db.photos,
+            status: LicenseStatus::effective(
+                &driver_license_db.status,
+                driver_license_db.expires_at,
+                Utc::now().date_naive(),
+            ),
+            rejection_reason: driver_license_db.rejection_reason,
+            reviewed_by: driver_license_db.reviewed_by,
+            reviewed_at: driver_license_db.reviewed_at,
+            created_at: driver_license_db.created_at,
+            updated_at: driver_license_db.updated_at,
+        }
+    }
+}
+
+/// Photos are URLs of the already uploaded license scans.
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SubmitLicenseRequest {
+    pub license_number: String,
+    pub categories: Vec<String>,
+    pub issued_at: NaiveDate,
+    pub expires_at: NaiveDate,
+    pub photos: Vec<String>,
+}
+
+impl Validate for SubmitLicenseRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_not_blank(&mut errors, "license_number", &self.license_number);
+        check_max_length(&mut errors, "license_number", &self.license_number, 50);
+
+        if self.categories.is_empty() {
+            errors.push(FieldError::new("categories", "must not be empty"));
+        }
+        if self.photos.is_empty() {
+            errors.push(FieldError::new("photos", "must not be empty"));
+        }
+        if self.issued_at > Utc::now().date_naive() {
+            errors.push(FieldError::new("issued_at", "must not be in the future"));
+        }
+        if self.expires_at <= self.issued_at {
+            errors.push(FieldError::new("expires_at", "must be after issued_at"));
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RejectLicenseRequest {
+    pub reason: String,
+}
+
+impl Validate for RejectLicenseRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_not_blank(&mut errors, "reason", &self.reason);
+
+        errors
+    }
+}
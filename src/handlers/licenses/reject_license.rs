@@ -0,0 +1,50 @@
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::licenses::{DriverLicenseResponse, RejectLicenseRequest};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::driver_licenses_service;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::license_status::LicenseStatus;
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/licenses/reject/{id}",
+    tag = "licenses",
+    summary = "Reject a driver's license",
+    request_body = RejectLicenseRequest,
+    params(("id" = Uuid, Path, description = "Driver's license id")),
+    responses(
+        (status = 200, description = "Rejected driver's license", body = DriverLicenseResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed, or the reviewer's own license", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Already reviewed", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn reject_license(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(license_id): Path<Uuid>,
+    ValidJson(reject_request): ValidJson<RejectLicenseRequest>,
+) -> Result<Json<DriverLicenseResponse>, HandlerError> {
+    debug!("->> {:<12} - reject_license", "HANDLER");
+
+    let license = driver_licenses_service::review(
+        &pool,
+        license_id,
+        Option::from(user_data.user_id),
+        LicenseStatus::Rejected,
+        Option::from(reject_request.reason),
+    )
+    .await
+    .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(license))
+}
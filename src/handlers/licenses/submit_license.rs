@@ -0,0 +1,49 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::licenses::{DriverLicenseResponse, SubmitLicenseRequest};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::{
+    driver_licenses_service, driver_licenses_service::NewDriverLicenseDb,
+};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/me/license",
+    tag = "licenses",
+    summary = "Submit a driver's license for review",
+    request_body = SubmitLicenseRequest,
+    responses(
+        (status = 200, description = "Submitted driver's license", body = DriverLicenseResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn submit_license(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    ValidJson(payload): ValidJson<SubmitLicenseRequest>,
+) -> Result<Json<DriverLicenseResponse>, HandlerError> {
+    debug!("->> {:<12} - submit_license", "HANDLER");
+
+    let new_driver_license = NewDriverLicenseDb {
+        user_id: user_data.user_id,
+        license_number: payload.license_number,
+        categories: payload.categories,
+        issued_at: payload.issued_at,
+        expires_at: payload.expires_at,
+        photos: payload.photos,
+    };
+
+    let license = driver_licenses_service::submit(&pool, new_driver_license)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(license))
+}
@@ -0,0 +1,35 @@
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use tracing::debug;
+
+use crate::handlers::DbPool;
+use crate::infra::metrics;
+use crate::infra::services::metrics_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "metrics",
+    summary = "Get metrics in the Prometheus text format",
+    description = "Served without authentication on `METRICS_PORT` when it's set.",
+    responses(
+        (status = 200, description = "Metrics", body = String, content_type = "text/plain"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_metrics(State(pool): State<DbPool>) -> Result<impl IntoResponse, HandlerError> {
+    debug!("->> {:<12} - get_metrics", "HANDLER");
+
+    let business_metrics = metrics_service::get_business_metrics(&pool)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    metrics::record_business_metrics(&business_metrics);
+    metrics::record_pool_state(&pool);
+
+    Ok(([(CONTENT_TYPE, "text/plain; version=0.0.4")], metrics::render()))
+}
@@ -5,14 +5,23 @@ use diesel_async::{
     pooled_connection::{bb8::Pool, AsyncDieselConnectionManager},
 };
 
+pub mod api_tokens;
 pub mod auth;
 pub mod cars;
+pub mod dashboard;
+pub mod health;
+pub mod licenses;
+pub mod metrics;
 pub mod orders;
+pub mod reports;
+pub mod sessions;
+pub mod users;
+pub mod validation;
 
 pub type DbPool = Pool<AsyncPgConnection>;
 
 pub async fn get_conn(
     pool: &DbPool,
-) -> Result<PooledConnection<AsyncDieselConnectionManager<AsyncPgConnection>>, DieselError> {
+) -> Result<PooledConnection<'_, AsyncDieselConnectionManager<AsyncPgConnection>>, DieselError> {
     pool.get().await.map_err(|e| QueryBuilderError(e.into()))
 }
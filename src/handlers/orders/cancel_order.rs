@@ -1,15 +1,30 @@
 use axum::{Extension, Json};
 use axum::extract::{Path, State};
 use chrono::Utc;
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::handlers::auth::UserData;
 use crate::handlers::DbPool;
 use crate::handlers::orders::UpdateOrderDb;
 use crate::infra::services::orders_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::permission::Permission;
 
+#[utoipa::path(
+    patch,
+    path = "/api/v1/orders/cancel/{id}",
+    tag = "orders",
+    summary = "Cancel an order",
+    params(("id" = Uuid, Path, description = "Order id")),
+    responses(
+        (status = 200, description = "Order cancelled", body = String),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn cancel_order(
     State(pool): State<DbPool>,
     Extension(user_data): Extension<UserData>,
@@ -22,7 +37,10 @@ pub async fn cancel_order(
         .map_err(HandlerError::CarSharingError)?
         .user_id;
 
-    if user_id_of_order == user_data.user_id {
+    // Staff with the permission can cancel any order
+    if (user_id_of_order == user_data.user_id && user_data.has_scope(Permission::MyOrdersWrite))
+        || user_data.has_permission(Permission::OrdersCancel)
+    {
         let now = Utc::now();
 
         let cancel_request = UpdateOrderDb {
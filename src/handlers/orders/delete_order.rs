@@ -1,11 +1,25 @@
 use axum::extract::{Path, State};
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::handlers::DbPool;
 use crate::infra::services::orders_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/orders/{id}",
+    tag = "orders",
+    summary = "Delete an order",
+    params(("id" = Uuid, Path, description = "Order id")),
+    responses(
+        (status = 200, description = "Order deleted", body = String),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn delete_order(
     State(pool): State<DbPool>,
     Path(order_id): Path<Uuid>,
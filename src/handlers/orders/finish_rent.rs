@@ -1,14 +1,28 @@
 use axum::extract::{Path, State};
 use axum::Json;
 use chrono::Utc;
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
 use crate::handlers::DbPool;
 use crate::handlers::orders::{OrderResponse, UpdateOrderDb};
 use crate::infra::services::orders_service;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    patch,
+    path = "/api/v1/orders/finish/{id}",
+    tag = "orders",
+    summary = "Finish the rent of an order",
+    params(("id" = Uuid, Path, description = "Order id")),
+    responses(
+        (status = 200, description = "Updated order", body = OrderResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn finish_rent(
     State(pool): State<DbPool>,
     Path(order_id): Path<Uuid>,
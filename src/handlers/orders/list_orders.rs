@@ -1,14 +1,27 @@
 use axum::extract::{Query, State};
 use axum::Json;
-use tracing::log::debug;
+use tracing::debug;
 
 use crate::handlers::DbPool;
 use crate::handlers::orders::OrderResponse;
 use crate::infra::services::orders_service;
 use crate::infra::services::orders_service::OrdersFilter;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 use crate::models::HandlerError::CarSharingError;
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/orders",
+    tag = "orders",
+    summary = "List orders",
+    params(OrdersFilter),
+    responses(
+        (status = 200, description = "Orders", body = Vec<OrderResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn list_orders(
     State(pool): State<DbPool>,
     Query(params): Query<OrdersFilter>,
@@ -17,7 +30,7 @@ pub async fn list_orders(
 
     let orders = orders_service::get_all(&pool, params)
         .await
-        .map_err(|err| CarSharingError(err))?;
+        .map_err(CarSharingError)?;
 
     Ok(Json(orders))
 }
@@ -1,23 +1,54 @@
 use axum::{Extension, Json};
 use axum::extract::State;
-use tracing::log::debug;
+use tracing::debug;
 
+use crate::config::config;
 use crate::handlers::auth::UserData;
 use crate::handlers::DbPool;
 use crate::handlers::orders::{MakeOrderRequest, OrderResponse};
-use crate::infra::services::orders_service;
-use crate::models::HandlerError;
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::{driver_licenses_service, orders_service};
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::user_status::UserStatus;
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/orders",
+    tag = "orders",
+    summary = "Order a car",
+    request_body = MakeOrderRequest,
+    responses(
+        (status = 200, description = "Created order", body = OrderResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 409, description = "Conflicts with existing data", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn make_order(
     State(pool): State<DbPool>,
     Extension(user_data): Extension<UserData>,
-    Json(make_order_request): Json<MakeOrderRequest>,
+    ValidJson(make_order_request): ValidJson<MakeOrderRequest>,
 ) -> Result<Json<OrderResponse>, HandlerError> {
     debug!("->> {:<12} - make_order", "HANDLER");
 
+    if user_data.status != UserStatus::Active {
+        return Err(HandlerError::UserRestricted);
+    }
+
+    if config().await.license_required() {
+        let today = chrono::Utc::now().date_naive();
+
+        if !driver_licenses_service::has_valid_license(&pool, user_data.user_id, today).await? {
+            return Err(HandlerError::LicenseRequired);
+        }
+    }
+
     let new_order_db = orders_service::NewOrderDb {
         user_id: user_data.user_id,
         car_id: make_order_request.car_id,
+        due_at: make_order_request.due_at,
     };
 
     let order = orders_service::insert(&pool, new_order_db).await?;
@@ -1,12 +1,24 @@
+use std::time::Duration;
+
+use axum::response::sse::Event;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::validation::Validate;
+use crate::infra::order_events::OrderEvent;
+use crate::infra::services::{api_tokens_service, sessions_service};
 use crate::infra::services::orders_service::OrderDb;
+use crate::models::FieldError;
+use crate::models::user_status::UserStatus;
 
 // User:
 pub mod cancel_order;
 pub mod make_order;
+pub mod my_order_events;
 pub mod orders_history;
 // Admin
 pub mod accept_order;
@@ -14,10 +26,12 @@ pub mod delete_order;
 pub mod finish_rent;
 pub mod get_order;
 pub mod list_orders;
+pub mod order_events;
+pub mod set_due_at;
 pub mod set_paid;
 pub mod start_rent;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct OrderResponse {
     pub id: Uuid,
     pub user_id: Uuid,
@@ -28,6 +42,10 @@ pub struct OrderResponse {
     pub paid: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
+    /// When the customer plans to bring the car back, in UTC, if they said.
+    pub due_at: Option<NaiveDateTime>,
+    /// Price of the rental at the car's rates when it finished, unset until then.
+    pub price: Option<i32>,
 }
 
 impl From<OrderDb> for OrderResponse {
@@ -42,13 +60,55 @@ impl From<OrderDb> for OrderResponse {
             paid: order_db.paid,
             created_at: order_db.created_at,
             updated_at: order_db.updated_at,
+            due_at: order_db.due_at,
+            price: order_db.price,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
 pub struct MakeOrderRequest {
     car_id: Uuid,
+    /// When the customer plans to bring the car back, in UTC. Optional, but must be in the
+    /// future when set.
+    #[serde(default)]
+    due_at: Option<NaiveDateTime>,
+}
+
+impl Validate for MakeOrderRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if let Some(due_at) = self.due_at {
+            check_due_at(&mut errors, due_at);
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SetDueAtRequest {
+    /// When the car is due back, in UTC. Must be in the future.
+    pub due_at: NaiveDateTime,
+}
+
+impl Validate for SetDueAtRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_due_at(&mut errors, self.due_at);
+
+        errors
+    }
+}
+
+fn check_due_at(errors: &mut Vec<FieldError>, due_at: NaiveDateTime) {
+    if due_at <= chrono::Utc::now().naive_utc() {
+        errors.push(FieldError::new("due_at", "must be in the future"));
+    }
 }
 
 #[derive(Debug)]
@@ -59,3 +119,32 @@ pub struct UpdateOrderDb {
     pub paid: Option<bool>,
     pub updated_at: Option<NaiveDateTime>,
 }
+
+/// Names the event after the change, so browsers can listen to the ones they care about.
+pub fn sse_event(event: &OrderEvent) -> Result<Event, axum::Error> {
+    Event::default().event(event.event.as_str()).json_data(event)
+}
+
+/// How often the session or API token an event stream was opened with is checked again.
+const STREAM_RECHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Resolves once the session or API token of a stream is gone, expired or revoked, its
+/// user blocked, or `allowed` no longer holds for them, e.g. after a change of role.
+pub async fn stream_revoked(pool: DbPool, user_data: UserData, allowed: fn(&UserData) -> bool) {
+    let mut interval = tokio::time::interval(STREAM_RECHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let current = match (user_data.session_id, user_data.api_token_id) {
+            (Some(session_id), _) => sessions_service::get_user_data(&pool, session_id).await,
+            (None, Some(api_token_id)) => api_tokens_service::get_user_data(&pool, api_token_id).await,
+            (None, None) => return,
+        };
+
+        match current {
+            Ok(current) if current.status != UserStatus::Blocked && allowed(&current) => {}
+            _ => return,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use axum::Extension;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::{future, Stream, StreamExt};
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::orders::{sse_event, stream_revoked};
+use crate::infra::order_events::{OrderEvent, OrderEvents};
+use crate::models::ErrorResponse;
+use crate::models::permission::Permission;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/orders/events/mine",
+    tag = "orders",
+    summary = "Stream the events of my orders",
+    description = "Same as `/api/v1/orders/events`, limited to the orders of the current user.",
+    responses(
+        (status = 200, description = "Order events", body = OrderEvent, content_type = "text/event-stream"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn my_order_events(
+    Extension(events): Extension<OrderEvents>,
+    Extension(user_data): Extension<UserData>,
+    State(pool): State<DbPool>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    debug!("->> {:<12} - my_order_events", "HANDLER");
+
+    let user_id = user_data.user_id;
+    let revoked = stream_revoked(pool, user_data, |user_data| {
+        user_data.has_scope(Permission::MyOrdersRead)
+    });
+
+    let stream = events
+        .subscribe()
+        .filter(move |event| future::ready(event.user_id == user_id))
+        .map(|event| sse_event(&event))
+        .take_until(revoked);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,47 @@
+use axum::Extension;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::{Stream, StreamExt};
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::orders::{sse_event, stream_revoked};
+use crate::infra::order_events::{OrderEvent, OrderEvents};
+use crate::models::ErrorResponse;
+use crate::models::permission::Permission;
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/orders/events",
+    tag = "orders",
+    summary = "Stream the events of every order",
+    description = "Server-sent events named after the change, e.g. `created` or `paid`, with the \
+                   order as data. Only events happening while connected are sent. The stream \
+                   ends once the session or API token is no longer valid, or the user \
+                   loses access to the orders.",
+    responses(
+        (status = 200, description = "Order events", body = OrderEvent, content_type = "text/event-stream"),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn order_events(
+    Extension(events): Extension<OrderEvents>,
+    Extension(user_data): Extension<UserData>,
+    State(pool): State<DbPool>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    debug!("->> {:<12} - order_events", "HANDLER");
+
+    let revoked = stream_revoked(pool, user_data, |user_data| {
+        user_data.has_permission(Permission::OrdersRead)
+    });
+
+    let stream = events
+        .subscribe()
+        .map(|event| sse_event(&event))
+        .take_until(revoked);
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
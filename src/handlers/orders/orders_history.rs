@@ -1,15 +1,26 @@
 use axum::{Extension, Json};
 use axum::extract::State;
-use tracing::log::debug;
+use tracing::debug;
 
-use crate::error::CarSharingError;
 use crate::handlers::auth::UserData;
 use crate::handlers::DbPool;
 use crate::handlers::orders::OrderResponse;
 use crate::infra::services::orders_service;
 use crate::infra::services::orders_service::OrdersFilter;
-use crate::models::HandlerError;
+use crate::models::{ErrorResponse, HandlerError};
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/orders/history",
+    tag = "orders",
+    summary = "List the current user's orders",
+    responses(
+        (status = 200, description = "Orders", body = Vec<OrderResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
 pub async fn orders_history(
     State(pool): State<DbPool>,
     Extension(user_data): Extension<UserData>,
@@ -22,7 +33,7 @@ pub async fn orders_history(
 
     let orders = orders_service::get_all(&pool, filter)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(HandlerError::CarSharingError)?;
 
     Ok(Json(orders))
 }
@@ -0,0 +1,43 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::DbPool;
+use crate::handlers::orders::{OrderResponse, SetDueAtRequest};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::orders_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/orders/due/{id}",
+    tag = "orders",
+    summary = "Set when the car of an order is due back",
+    description = "Sets or changes `due_at` on an order that isn't finished or cancelled, e.g. \
+                   when accepting it or handing the car over.",
+    params(("id" = Uuid, Path, description = "Order id")),
+    request_body = SetDueAtRequest,
+    responses(
+        (status = 200, description = "Updated order", body = OrderResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "Order already finished or cancelled", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn set_due_at(
+    State(pool): State<DbPool>,
+    Path(order_id): Path<Uuid>,
+    ValidJson(set_due_at_request): ValidJson<SetDueAtRequest>,
+) -> Result<Json<OrderResponse>, HandlerError> {
+    debug!("->> {:<12} - set_due_at", "HANDLER");
+
+    let order = orders_service::set_due_at(&pool, order_id, set_due_at_request.due_at)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(order))
+}
@@ -0,0 +1,56 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use tracing::debug;
+
+use crate::handlers::DbPool;
+use crate::handlers::reports::{
+    csv_download, report_days, report_window, revenue_csv, ReportFormat, RevenueReport,
+    RevenueReportParams,
+};
+use crate::infra::services::reports_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/revenue",
+    tag = "reports",
+    summary = "Get orders and revenue by car, period or status",
+    params(RevenueReportParams),
+    responses(
+        (status = 200, description = "Revenue report", content(
+            (RevenueReport = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid days", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_revenue_report(
+    State(pool): State<DbPool>,
+    Query(params): Query<RevenueReportParams>,
+) -> Result<Response, HandlerError> {
+    debug!("->> {:<12} - get_revenue_report", "HANDLER");
+
+    let (from, to) = report_days(params.from, params.to)?;
+    let (start, end) = report_window(from, to);
+
+    let rows = reports_service::get_revenue(&pool, params.group_by, start, end)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    let response = match params.format {
+        ReportFormat::Json => Json(RevenueReport {
+            from,
+            to,
+            group_by: params.group_by,
+            rows,
+        })
+        .into_response(),
+        ReportFormat::Csv => csv_download("revenue", from, to, revenue_csv(&rows)),
+    };
+
+    Ok(response)
+}
@@ -0,0 +1,54 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use axum::response::{IntoResponse, Response};
+use chrono::Utc;
+use tracing::debug;
+
+use crate::handlers::DbPool;
+use crate::handlers::reports::{
+    csv_download, report_days, report_window, utilization_csv, ReportFormat, UtilizationReport,
+    UtilizationReportParams,
+};
+use crate::infra::services::reports_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/reports/utilization",
+    tag = "reports",
+    summary = "Get the share of time each car was rented",
+    params(UtilizationReportParams),
+    responses(
+        (status = 200, description = "Utilization report", content(
+            (UtilizationReport = "application/json"),
+            (String = "text/csv"),
+        )),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid days", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_utilization_report(
+    State(pool): State<DbPool>,
+    Query(params): Query<UtilizationReportParams>,
+) -> Result<Response, HandlerError> {
+    debug!("->> {:<12} - get_utilization_report", "HANDLER");
+
+    let (from, to) = report_days(params.from, params.to)?;
+    let (start, end) = report_window(from, to);
+
+    // The rest of today hasn't happened yet, so it can't count as idle time
+    let end = end.min(Utc::now().naive_utc());
+
+    let rows = reports_service::get_utilization(&pool, start, end)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    let response = match params.format {
+        ReportFormat::Json => Json(UtilizationReport { from, to, rows }).into_response(),
+        ReportFormat::Csv => csv_download("utilization", from, to, utilization_csv(&rows)),
+    };
+
+    Ok(response)
+}
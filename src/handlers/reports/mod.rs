@@ -0,0 +1,236 @@
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::infra::csv;
+use crate::infra::services::reports_service::{RevenueRowDb, UtilizationRowDb};
+use crate::models::{FieldError, HandlerError};
+
+pub mod get_revenue_report;
+pub mod get_utilization_report;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RevenueGrouping {
+    #[default]
+    Car,
+    Day,
+    /// Weeks start on Monday.
+    Week,
+    Month,
+    Status,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    #[default]
+    Json,
+    /// Downloaded as a file with the rows only.
+    Csv,
+}
+
+/// Query of `get_revenue_report`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct RevenueReportParams {
+    /// First day of the report, the first day of the current month by default.
+    pub from: Option<NaiveDate>,
+    /// Last day of the report, included, today by default.
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub group_by: RevenueGrouping,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+/// Query of `get_utilization_report`.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UtilizationReportParams {
+    /// First day of the report, the first day of the current month by default.
+    pub from: Option<NaiveDate>,
+    /// Last day of the report, included, today by default.
+    pub to: Option<NaiveDate>,
+    #[serde(default)]
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevenueReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub group_by: RevenueGrouping,
+    pub rows: Vec<RevenueReportRow>,
+}
+
+/// Totals of the orders placed, or whose rental started, on the reported days. Prices are
+/// fixed at the car's rates when the rental finishes, so only finished rentals have one.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RevenueReportRow {
+    /// Car id, first day of the period or order status, depending on `group_by`.
+    pub key: String,
+    /// Car name when grouped by car.
+    pub label: Option<String>,
+    pub orders: i64,
+    pub rented_hours: f64,
+    pub revenue: i64,
+    pub paid_revenue: i64,
+    pub unpaid_revenue: i64,
+}
+
+impl From<RevenueRowDb> for RevenueReportRow {
+    fn from(row_db: RevenueRowDb) -> Self {
+        RevenueReportRow {
+            key: row_db.key,
+            label: row_db.label,
+            orders: row_db.orders,
+            rented_hours: row_db.rented_hours,
+            revenue: row_db.revenue,
+            paid_revenue: row_db.paid_revenue,
+            unpaid_revenue: row_db.revenue - row_db.paid_revenue,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UtilizationReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub rows: Vec<UtilizationReportRow>,
+}
+
+/// How long each car was rented on the reported days, up to now for the current day.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UtilizationReportRow {
+    pub car_id: Uuid,
+    pub name: String,
+    pub license_plate: Option<String>,
+    /// Rentals overlapping the reported days.
+    pub orders: i64,
+    pub rented_hours: f64,
+    /// Percentage of the reported time elapsed so far the car was rented.
+    pub utilization: f64,
+}
+
+impl From<UtilizationRowDb> for UtilizationReportRow {
+    fn from(row_db: UtilizationRowDb) -> Self {
+        UtilizationReportRow {
+            car_id: row_db.car_id,
+            name: row_db.name,
+            license_plate: row_db.license_plate,
+            orders: row_db.orders,
+            rented_hours: row_db.rented_hours,
+            utilization: row_db.utilization,
+        }
+    }
+}
+
+/// Fills in the default days of a report and checks they're in order.
+pub fn report_days(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<(NaiveDate, NaiveDate), HandlerError> {
+    let today = Utc::now().date_naive();
+
+    let to = to.unwrap_or(today);
+    let from = from.unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+
+    if from > to {
+        return Err(HandlerError::ValidationError(vec![FieldError::new(
+            "from",
+            "must not be after `to`",
+        )]));
+    }
+
+    Ok((from, to))
+}
+
+/// Start of the reported time, and its end as the midnight after the last day.
+pub fn report_window(from: NaiveDate, to: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+    let end = to.succ_opt().unwrap_or(to);
+
+    (from.and_time(Default::default()), end.and_time(Default::default()))
+}
+
+pub fn revenue_csv(rows: &[RevenueReportRow]) -> String {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        &["key", "label", "orders", "rented_hours", "revenue", "paid_revenue", "unpaid_revenue"],
+    );
+
+    for row in rows {
+        csv::write_row(
+            &mut out,
+            &[
+                row.key.clone(),
+                row.label.clone().unwrap_or_default(),
+                row.orders.to_string(),
+                row.rented_hours.to_string(),
+                row.revenue.to_string(),
+                row.paid_revenue.to_string(),
+                row.unpaid_revenue.to_string(),
+            ],
+        );
+    }
+
+    out
+}
+
+pub fn utilization_csv(rows: &[UtilizationReportRow]) -> String {
+    let mut out = String::new();
+    csv::write_row(
+        &mut out,
+        &["car_id", "name", "license_plate", "orders", "rented_hours", "utilization"],
+    );
+
+    for row in rows {
+        csv::write_row(
+            &mut out,
+            &[
+                row.car_id.to_string(),
+                row.name.clone(),
+                row.license_plate.clone().unwrap_or_default(),
+                row.orders.to_string(),
+                row.rented_hours.to_string(),
+                row.utilization.to_string(),
+            ],
+        );
+    }
+
+    out
+}
+
+/// Serves a report as a CSV download named after it and its days.
+pub fn csv_download(report: &str, from: NaiveDate, to: NaiveDate, content: String) -> Response {
+    let disposition = format!("attachment; filename=\"{}-{}-{}.csv\"", report, from, to);
+
+    (
+        [
+            (CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (CONTENT_DISPOSITION, disposition),
+        ],
+        content,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_days() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+
+        assert_eq!(report_days(Some(day(1)), Some(day(1))).unwrap(), (day(1), day(1)));
+        assert!(report_days(Some(day(2)), Some(day(1))).is_err());
+
+        let (start, end) = report_window(day(1), day(19));
+        assert_eq!(end - start, chrono::Duration::days(19));
+    }
+}
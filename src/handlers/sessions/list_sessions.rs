@@ -0,0 +1,37 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::sessions::SessionResponse;
+use crate::infra::services::sessions_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/sessions",
+    tag = "sessions",
+    summary = "List the current user's sessions",
+    responses(
+        (status = 200, description = "Sessions", body = Vec<SessionResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn list_sessions(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+) -> Result<Json<Vec<SessionResponse>>, HandlerError> {
+    debug!("->> {:<12} - list_sessions", "HANDLER");
+
+    let sessions = sessions_service::get_user_sessions(&pool, user_data.user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?
+        .into_iter()
+        .map(|session_db| SessionResponse::new(session_db, user_data.session_id))
+        .collect();
+
+    Ok(Json(sessions))
+}
@@ -0,0 +1,36 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::infra::services::sessions_service::SessionDb;
+
+pub mod list_sessions;
+pub mod revoke_all_sessions;
+pub mod revoke_session;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SessionResponse {
+    pub id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
+    /// Whether this is the session the request was made with.
+    pub current: bool,
+}
+
+impl SessionResponse {
+    pub fn new(session_db: SessionDb, current_session_id: Option<Uuid>) -> Self {
+        SessionResponse {
+            id: session_db.id,
+            created_at: session_db.created_at,
+            last_seen_at: session_db.last_seen_at,
+            expires_at: session_db.expires_at,
+            user_agent: session_db.user_agent,
+            ip: session_db.ip,
+            current: Option::from(session_db.id) == current_session_id,
+        }
+    }
+}
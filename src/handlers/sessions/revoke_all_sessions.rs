@@ -0,0 +1,38 @@
+use axum::Extension;
+use axum::extract::State;
+use tower_cookies::Cookies;
+use tracing::debug;
+
+use crate::handlers::auth::{removal_session_cookie, UserData};
+use crate::handlers::DbPool;
+use crate::infra::services::sessions_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+/// Logs the user out on every device, including the current one.
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions",
+    tag = "sessions",
+    summary = "Revoke all sessions of the current user",
+    responses(
+        (status = 200, description = "Sessions revoked", body = String),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn revoke_all_sessions(
+    cookies: Cookies,
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+) -> Result<String, HandlerError> {
+    debug!("->> {:<12} - revoke_all_sessions", "HANDLER");
+
+    sessions_service::delete_user_sessions(&pool, user_data.user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    cookies.remove(removal_session_cookie().await);
+
+    Ok("All sessions were successfully revoked!".to_string())
+}
@@ -0,0 +1,43 @@
+use axum::Extension;
+use axum::extract::{Path, State};
+use tower_cookies::Cookies;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::{removal_session_cookie, UserData};
+use crate::handlers::DbPool;
+use crate::infra::services::sessions_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/sessions/{id}",
+    tag = "sessions",
+    summary = "Revoke a session of the current user",
+    params(("id" = Uuid, Path, description = "Session id")),
+    responses(
+        (status = 200, description = "Session revoked", body = String),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn revoke_session(
+    cookies: Cookies,
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(session_id): Path<Uuid>,
+) -> Result<String, HandlerError> {
+    debug!("->> {:<12} - revoke_session", "HANDLER");
+
+    sessions_service::delete_user_session(&pool, user_data.user_id, session_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    if Option::from(session_id) == user_data.session_id {
+        cookies.remove(removal_session_cookie().await);
+    }
+
+    Ok("Session was successfully revoked!".to_string())
+}
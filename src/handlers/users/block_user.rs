@@ -0,0 +1,56 @@
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::users::{BlockUserRequest, UserResponse};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::users_service;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::user_status::UserStatus;
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/block/{id}",
+    tag = "users",
+    summary = "Block or restrict a user",
+    request_body = BlockUserRequest,
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Updated user", body = UserResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "No other active admin would remain", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn block_user(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(user_id): Path<Uuid>,
+    ValidJson(block_request): ValidJson<BlockUserRequest>,
+) -> Result<Json<UserResponse>, HandlerError> {
+    debug!("->> {:<12} - block_user", "HANDLER");
+
+    // Admins blocking themselves could lock everyone out
+    if user_id == user_data.user_id {
+        return Err(HandlerError::OwnAccount);
+    }
+
+    let user = users_service::set_status(
+        &pool,
+        user_id,
+        user_data.user_id,
+        UserStatus::from(block_request.status),
+        Option::from(block_request.reason),
+        block_request.expires_at,
+    )
+    .await
+    .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(user))
+}
@@ -0,0 +1,40 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use chrono::Utc;
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::users::MeResponse;
+use crate::infra::services::{driver_licenses_service, users_service};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/me",
+    tag = "users",
+    summary = "Get the current user",
+    responses(
+        (status = 200, description = "Current user with their license status", body = MeResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_me(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+) -> Result<Json<MeResponse>, HandlerError> {
+    debug!("->> {:<12} - get_me", "HANDLER");
+
+    let user = users_service::get(&pool, user_data.user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    let today = Utc::now().date_naive();
+    let license_status = driver_licenses_service::get_status_by_user(&pool, user_data.user_id, today)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(MeResponse { user, license_status }))
+}
@@ -0,0 +1,43 @@
+use axum::extract::{Path, State};
+use axum::Json;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::DbPool;
+use crate::handlers::users::AdminUserResponse;
+use crate::infra::services::users_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users/{id}",
+    tag = "users",
+    summary = "Get a user with their block history",
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "User", body = AdminUserResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn get_user(
+    State(pool): State<DbPool>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<AdminUserResponse>, HandlerError> {
+    debug!("->> {:<12} - get_user", "HANDLER");
+
+    let user = users_service::get(&pool, user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    let status_events = users_service::get_status_events(&pool, user_id)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(AdminUserResponse {
+        user,
+        status_events,
+    }))
+}
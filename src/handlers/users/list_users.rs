@@ -0,0 +1,34 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use tracing::debug;
+
+use crate::handlers::DbPool;
+use crate::handlers::users::UserResponse;
+use crate::infra::services::{users_service, users_service::UsersFilter};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/users",
+    tag = "users",
+    summary = "List users",
+    params(UsersFilter),
+    responses(
+        (status = 200, description = "Users", body = Vec<UserResponse>),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn list_users(
+    State(pool): State<DbPool>,
+    Query(params): Query<UsersFilter>,
+) -> Result<Json<Vec<UserResponse>>, HandlerError> {
+    debug!("->> {:<12} - list_users", "HANDLER");
+
+    let users = users_service::get_all(&pool, params)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(users))
+}
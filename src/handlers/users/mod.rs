@@ -0,0 +1,220 @@
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::handlers::validation::{
+    check_max_length, check_not_blank, deserialize_nullable, Validate,
+};
+use crate::infra::services::users_service::{UserDb, UserStatusEventDb};
+use crate::models::FieldError;
+use crate::models::license_status::LicenseStatus;
+use crate::models::permission::Permission;
+use crate::models::role::Role;
+use crate::models::user_status::UserStatus;
+
+// User:
+pub mod get_me;
+pub mod update_me;
+// Admin
+pub mod block_user;
+pub mod get_user;
+pub mod list_users;
+pub mod unblock_user;
+pub mod update_role;
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub telegram_id: i64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub photo_url: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub role: String,
+    pub permissions: Vec<Permission>,
+    pub status: UserStatus,
+    pub status_reason: Option<String>,
+    pub status_until: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: Option<NaiveDateTime>,
+}
+
+/// The current user, with the verification state of their driver's license.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MeResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    /// `null` until a license is submitted.
+    pub license_status: Option<LicenseStatus>,
+}
+
+impl From<UserDb> for UserResponse {
+    fn from(user_db: UserDb) -> Self {
+        UserResponse {
+            id: user_db.id,
+            telegram_id: user_db.telegram_id,
+            first_name: user_db.first_name,
+            last_name: user_db.last_name,
+            username: user_db.username,
+            photo_url: user_db.photo_url,
+            phone_number: user_db.phone_number,
+            email: user_db.email,
+            permissions: Role::from(user_db.role.as_str()).permissions().to_vec(),
+            role: user_db.role,
+            status: UserStatus::effective(
+                &user_db.status,
+                user_db.status_until,
+                chrono::Utc::now().naive_utc(),
+            ),
+            status_reason: user_db.status_reason,
+            status_until: user_db.status_until,
+            created_at: user_db.created_at,
+            updated_at: user_db.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateProfileRequest {
+    /// Removed when `null`, kept as is when missing.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub phone_number: Option<Option<String>>,
+    /// Removed when `null`, kept as is when missing.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub email: Option<Option<String>>,
+}
+
+impl UpdateProfileRequest {
+    pub fn is_empty(&self) -> bool {
+        self.phone_number.is_none() && self.email.is_none()
+    }
+}
+
+impl Validate for UpdateProfileRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        if self.is_empty() {
+            errors.push(FieldError::new("body", "at least one field must be set"));
+        }
+        if let Some(Some(phone_number)) = &self.phone_number {
+            // E.164: a plus followed by up to 15 digits
+            let digits = phone_number.strip_prefix('+').unwrap_or_default();
+            if !(7..=15).contains(&digits.len()) || !digits.chars().all(|c| c.is_ascii_digit()) {
+                errors.push(FieldError::new(
+                    "phone_number",
+                    "must be in international format, e.g. +10000000000",
+                ));
+            }
+        }
+        if let Some(Some(email)) = &self.email {
+            let is_email = email
+                .split_once('@')
+                .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+            if !is_email {
+                errors.push(FieldError::new("email", "must be a valid email address"));
+            }
+            check_max_length(&mut errors, "email", email, 254);
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateRoleRequest {
+    pub role: Role,
+}
+
+impl Validate for UpdateRoleRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+}
+
+/// User as seen by admins, including the history of blocks and unblocks.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AdminUserResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub status_events: Vec<UserStatusEventResponse>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct UserStatusEventResponse {
+    pub id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub status: UserStatus,
+    pub reason: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<UserStatusEventDb> for UserStatusEventResponse {
+    fn from(event_db: UserStatusEventDb) -> Self {
+        UserStatusEventResponse {
+            id: event_db.id,
+            admin_id: event_db.admin_id,
+            status: UserStatus::from(event_db.status.as_str()),
+            reason: event_db.reason,
+            expires_at: event_db.expires_at,
+            created_at: event_db.created_at,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    /// Forbid new orders only.
+    Restricted,
+    /// Forbid any access and revoke all sessions.
+    Blocked,
+}
+
+impl From<BlockKind> for UserStatus {
+    fn from(kind: BlockKind) -> Self {
+        match kind {
+            BlockKind::Restricted => UserStatus::Restricted,
+            BlockKind::Blocked => UserStatus::Blocked,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct BlockUserRequest {
+    pub status: BlockKind,
+    pub reason: String,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl Validate for BlockUserRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        let mut errors = Vec::new();
+
+        check_not_blank(&mut errors, "reason", &self.reason);
+        if self.expires_at.is_some_and(|expires_at| expires_at <= Utc::now().naive_utc()) {
+            errors.push(FieldError::new("expires_at", "must be in the future"));
+        }
+
+        errors
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UnblockUserRequest {
+    pub reason: Option<String>,
+}
+
+impl Validate for UnblockUserRequest {
+    fn validate(&self) -> Vec<FieldError> {
+        Vec::new()
+    }
+}
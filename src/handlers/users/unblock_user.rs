@@ -0,0 +1,55 @@
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::users::{UnblockUserRequest, UserResponse};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::users_service;
+use crate::models::{ErrorResponse, HandlerError};
+use crate::models::user_status::UserStatus;
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/unblock/{id}",
+    tag = "users",
+    summary = "Lift a user's block or restriction",
+    request_body = UnblockUserRequest,
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Updated user", body = UserResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn unblock_user(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(user_id): Path<Uuid>,
+    ValidJson(unblock_request): ValidJson<UnblockUserRequest>,
+) -> Result<Json<UserResponse>, HandlerError> {
+    debug!("->> {:<12} - unblock_user", "HANDLER");
+
+    // Restricted admins can't lift their own restriction
+    if user_id == user_data.user_id {
+        return Err(HandlerError::OwnAccount);
+    }
+
+    let user = users_service::set_status(
+        &pool,
+        user_id,
+        user_data.user_id,
+        UserStatus::Active,
+        unblock_request.reason,
+        None,
+    )
+    .await
+    .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(user))
+}
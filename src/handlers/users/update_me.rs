@@ -0,0 +1,44 @@
+use axum::{Extension, Json};
+use axum::extract::State;
+use chrono::Utc;
+use tracing::debug;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::users::{MeResponse, UpdateProfileRequest};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::{driver_licenses_service, users_service};
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/me",
+    tag = "users",
+    summary = "Update the current user's contacts",
+    request_body = UpdateProfileRequest,
+    responses(
+        (status = 200, description = "Updated user", body = MeResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn update_me(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    ValidJson(updated_profile): ValidJson<UpdateProfileRequest>,
+) -> Result<Json<MeResponse>, HandlerError> {
+    debug!("->> {:<12} - update_me", "HANDLER");
+
+    let user = users_service::update_profile(&pool, user_data.user_id, updated_profile)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    let today = Utc::now().date_naive();
+    let license_status = driver_licenses_service::get_status_by_user(&pool, user_data.user_id, today)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(MeResponse { user, license_status }))
+}
@@ -0,0 +1,48 @@
+use axum::{Extension, Json};
+use axum::extract::{Path, State};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::handlers::auth::UserData;
+use crate::handlers::DbPool;
+use crate::handlers::users::{UpdateRoleRequest, UserResponse};
+use crate::handlers::validation::ValidJson;
+use crate::infra::services::users_service;
+use crate::models::{ErrorResponse, HandlerError};
+
+#[utoipa::path(
+    patch,
+    path = "/api/v1/users/role/{id}",
+    tag = "users",
+    summary = "Change a user's role",
+    request_body = UpdateRoleRequest,
+    params(("id" = Uuid, Path, description = "User id")),
+    responses(
+        (status = 200, description = "Updated user", body = UserResponse),
+        (status = 401, description = "Not authenticated", body = ErrorResponse),
+        (status = 403, description = "Not allowed", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 409, description = "No other active admin would remain", body = ErrorResponse),
+        (status = 422, description = "Invalid request", body = ErrorResponse),
+    ),
+    security(("session_cookie" = []), ("api_token" = []))
+)]
+pub async fn update_role(
+    State(pool): State<DbPool>,
+    Extension(user_data): Extension<UserData>,
+    Path(user_id): Path<Uuid>,
+    ValidJson(update_role_request): ValidJson<UpdateRoleRequest>,
+) -> Result<Json<UserResponse>, HandlerError> {
+    debug!("->> {:<12} - update_role", "HANDLER");
+
+    // Admins demoting themselves could lock everyone out
+    if user_id == user_data.user_id {
+        return Err(HandlerError::OwnAccount);
+    }
+
+    let user = users_service::update_role(&pool, user_id, update_role_request.role)
+        .await
+        .map_err(HandlerError::CarSharingError)?;
+
+    Ok(Json(user))
+}
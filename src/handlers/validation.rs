@@ -0,0 +1,89 @@
+use axum::{async_trait, Json};
+use axum::extract::{FromRequest, Request};
+use serde::{Deserialize, Deserializer};
+use serde::de::DeserializeOwned;
+
+use crate::models::{FieldError, HandlerError};
+
+/// Business rules a request body must satisfy besides deserializing.
+pub trait Validate {
+    /// Returns every rule the value breaks, an empty list means it's valid.
+    fn validate(&self) -> Vec<FieldError>;
+}
+
+/// `Json` extractor that also runs `Validate`, rejecting the request with a 422
+/// listing the invalid fields. Malformed bodies get our error format as well.
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = HandlerError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(HandlerError::JsonRejection)?;
+
+        let field_errors = value.validate();
+
+        if field_errors.is_empty() {
+            Ok(ValidJson(value))
+        } else {
+            Err(HandlerError::ValidationError(field_errors))
+        }
+    }
+}
+
+/// Tells a field sent as `null`, read as `Some(None)`, from a missing one, read as `None`
+/// with `#[serde(default)]`. Changesets skip `None` but set `Some(None)` to NULL.
+pub fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+pub fn check_not_blank(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    if value.trim().is_empty() {
+        errors.push(FieldError::new(field, "must not be blank"));
+    }
+}
+
+/// Limits are in characters, matching `VARCHAR(n)` columns.
+pub fn check_max_length(errors: &mut Vec<FieldError>, field: &str, value: &str, max: usize) {
+    if value.chars().count() > max {
+        errors.push(FieldError::new(
+            field,
+            &format!("must be at most {} characters long", max),
+        ));
+    }
+}
+
+pub fn check_positive(errors: &mut Vec<FieldError>, field: &str, value: i32) {
+    if value <= 0 {
+        errors.push(FieldError::new(field, "must be greater than zero"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checks() {
+        let mut errors = Vec::new();
+
+        check_not_blank(&mut errors, "name", "  ");
+        check_max_length(&mut errors, "name", "ёёё", 3);
+        check_max_length(&mut errors, "name", "abcd", 3);
+        check_positive(&mut errors, "rate", 0);
+
+        let fields: Vec<&str> = errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["name", "name", "rate"]);
+    }
+}
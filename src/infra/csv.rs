@@ -0,0 +1,106 @@
+/// Parses RFC 4180 CSV into rows of fields, skipping blank lines.
+pub fn parse(input: &str) -> Result<Vec<Vec<String>>, String> {
+    // Spreadsheets like to start their exports with a byte order mark
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut quoted_since = None;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+
+        if quoted_since.is_some() {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted_since = None,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => quoted_since = Some(line),
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => end_row(&mut rows, &mut row, &mut field),
+            _ => field.push(c),
+        }
+    }
+
+    if let Some(line) = quoted_since {
+        return Err(format!("line {}: quoted field is never closed", line));
+    }
+    end_row(&mut rows, &mut row, &mut field);
+
+    Ok(rows)
+}
+
+fn end_row(rows: &mut Vec<Vec<String>>, row: &mut Vec<String>, field: &mut String) {
+    row.push(std::mem::take(field));
+
+    if row.len() == 1 && row[0].is_empty() {
+        row.clear();
+    } else {
+        rows.push(std::mem::take(row));
+    }
+}
+
+/// Appends a row, quoting the fields that need it.
+pub fn write_row<S: AsRef<str>>(out: &mut String, fields: &[S]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.push(',');
+        }
+
+        let field = field.as_ref();
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let rows = parse("\u{feff}name,photos\r\n\"Car, \"\"red\"\"\",\"a\nb\"\n\nplain,\n").unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec!["name", "photos"],
+                vec!["Car, \"red\"", "a\nb"],
+                vec!["plain", ""],
+            ]
+        );
+        assert_eq!(parse("a\n\"b,c\nd").unwrap_err(), "line 2: quoted field is never closed");
+    }
+
+    #[test]
+    fn test_write_row_round_trip() {
+        let fields = ["Car, \"red\"", "a\nb", "", "plain"];
+
+        let mut out = String::new();
+        write_row(&mut out, &fields);
+
+        assert_eq!(out, "\"Car, \"\"red\"\"\",\"a\nb\",,plain\r\n");
+        assert_eq!(parse(&out).unwrap(), vec![fields.to_vec()]);
+    }
+}
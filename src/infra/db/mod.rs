@@ -1,17 +1,130 @@
+use std::time::Duration;
+
+use diesel::{Connection, PgConnection};
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::sql_types::{Integer, Nullable, Timestamp};
+use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
-use tracing::log::debug;
+use tracing::{debug, warn};
+
+use crate::config::Config;
+use crate::error::ServerError;
+use crate::handlers::DbPool;
 
 pub mod schema;
 
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations/");
 
-pub fn run_migrations(url: &str) {
-    use diesel::prelude::*;
+diesel::sql_function! {
+    /// Price of a rental billed at the car's rates, NULL while it hasn't both started
+    /// and ended. Defined in the `create_rental_cost` migration, and stored as the price
+    /// of orders when they finish.
+    fn rental_cost(
+        start_time: Nullable<Timestamp>,
+        end_time: Nullable<Timestamp>,
+        hourly_rate: Integer,
+        daily_rate: Integer,
+        weekly_rate: Integer,
+    ) -> Nullable<Integer>;
+}
+
+/// Runs the pending migrations on a blocking thread, retrying while the database
+/// doesn't accept connections yet, e.g. when both are started together. Returns the
+/// versions of the migrations that were run.
+pub async fn run_migrations(
+    url: &str,
+    retries: u32,
+    retry_delay: u64,
+) -> Result<Vec<String>, ServerError> {
+    let mut attempt = 0;
+
+    loop {
+        let url = url.to_string();
+
+        let res = run_blocking(move || {
+            debug!("Running migrations");
+
+            // &mut impl MigrationHarness<diesel::pg::Pg>
+            establish(&url)?
+                .run_pending_migrations(MIGRATIONS)
+                .map(|versions| versions.iter().map(ToString::to_string).collect())
+                .map_err(|err| ServerError::Migrations(err.to_string()))
+        })
+        .await;
+
+        match res {
+            Err(ServerError::DatabaseUnreachable(err)) if attempt < retries => {
+                attempt += 1;
+                warn!(
+                    "Database unreachable, retrying in {}s ({}/{}): {}",
+                    retry_delay, attempt, retries, err
+                );
+                tokio::time::sleep(Duration::from_secs(retry_delay)).await;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// Reverts the latest migration, returning its version.
+pub async fn revert_last_migration(url: &str) -> Result<String, ServerError> {
+    let url = url.to_string();
+
+    run_blocking(move || {
+        establish(&url)?
+            .revert_last_migration(MIGRATIONS)
+            .map(|version| version.to_string())
+            .map_err(|err| ServerError::Migrations(err.to_string()))
+    })
+    .await
+}
+
+/// Names of all embedded migrations along with whether they've been run.
+pub async fn get_migrations_status(url: &str) -> Result<Vec<(String, bool)>, ServerError> {
+    let url = url.to_string();
+
+    run_blocking(move || {
+        let applied = establish(&url)?
+            .applied_migrations()
+            .map_err(|err| ServerError::Migrations(err.to_string()))?;
+
+        let migrations = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+            .map_err(|err| ServerError::Migrations(err.to_string()))?;
+
+        Ok(migrations
+            .iter()
+            .map(|migration| {
+                let name = migration.name();
+                (name.to_string(), applied.contains(&name.version()))
+            })
+            .collect())
+    })
+    .await
+}
+
+fn establish(url: &str) -> Result<PgConnection, ServerError> {
+    PgConnection::establish(url).map_err(ServerError::DatabaseUnreachable)
+}
+
+/// Diesel's migrations need a synchronous connection, which mustn't block the executor.
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, ServerError> + Send + 'static,
+) -> Result<T, ServerError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| ServerError::Migrations(err.to_string()))?
+}
 
-    debug!("Running migrations");
+pub async fn create_pool(config: &Config) -> Result<DbPool, ServerError> {
+    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
 
-    let mut conn = diesel::pg::PgConnection::establish(url).expect("Failed to connect to database");
-    // &mut impl MigrationHarness<diesel::pg::Pg>
-    conn.run_pending_migrations(MIGRATIONS)
-        .expect("Failed to run migrations");
+    bb8::Pool::builder()
+        .max_size(config.db_pool_max_size())
+        .min_idle(config.db_pool_min_idle())
+        .connection_timeout(Duration::from_secs(config.db_connection_timeout()))
+        .idle_timeout(Some(Duration::from_secs(config.db_idle_timeout())))
+        .build(manager)
+        .await
+        .map_err(ServerError::Pool)
 }
@@ -1,5 +1,21 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_tokens (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        created_by -> Nullable<Uuid>,
+        #[max_length = 100]
+        name -> Varchar,
+        token_hash -> Bytea,
+        scopes -> Array<Text>,
+        created_at -> Timestamp,
+        expires_at -> Timestamp,
+        last_used_at -> Nullable<Timestamp>,
+        revoked_at -> Nullable<Timestamp>,
+    }
+}
+
 diesel::table! {
     cars (id) {
         id -> Uuid,
@@ -12,6 +28,29 @@ diesel::table! {
         #[max_length = 30]
         status -> Varchar,
         created_at -> Timestamp,
+        #[max_length = 20]
+        license_plate -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    driver_licenses (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 50]
+        license_number -> Varchar,
+        categories -> Array<Text>,
+        issued_at -> Date,
+        expires_at -> Date,
+        photos -> Array<Text>,
+        #[max_length = 20]
+        status -> Varchar,
+        rejection_reason -> Nullable<Text>,
+        reviewed_by -> Nullable<Uuid>,
+        reviewed_at -> Nullable<Timestamp>,
+        expiry_notified_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -27,6 +66,8 @@ diesel::table! {
         paid -> Bool,
         created_at -> Timestamp,
         updated_at -> Nullable<Timestamp>,
+        due_at -> Nullable<Timestamp>,
+        price -> Nullable<Int4>,
     }
 }
 
@@ -34,6 +75,26 @@ diesel::table! {
     sessions (session_token) {
         session_token -> Bytea,
         user_id -> Uuid,
+        id -> Uuid,
+        created_at -> Timestamp,
+        last_seen_at -> Timestamp,
+        expires_at -> Timestamp,
+        user_agent -> Nullable<Text>,
+        #[max_length = 45]
+        ip -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    user_status_events (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        admin_id -> Nullable<Uuid>,
+        #[max_length = 20]
+        status -> Varchar,
+        reason -> Nullable<Text>,
+        expires_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
     }
 }
 
@@ -45,7 +106,21 @@ diesel::table! {
         created_at -> Timestamp,
         #[max_length = 20]
         status -> Varchar,
-        telegram_id -> Int4,
+        telegram_id -> Int8,
+        #[max_length = 64]
+        first_name -> Nullable<Varchar>,
+        #[max_length = 64]
+        last_name -> Nullable<Varchar>,
+        #[max_length = 32]
+        username -> Nullable<Varchar>,
+        photo_url -> Nullable<Text>,
+        #[max_length = 20]
+        phone_number -> Nullable<Varchar>,
+        #[max_length = 254]
+        email -> Nullable<Varchar>,
+        updated_at -> Nullable<Timestamp>,
+        status_reason -> Nullable<Text>,
+        status_until -> Nullable<Timestamp>,
     }
 }
 
@@ -54,8 +129,11 @@ diesel::joinable!(orders -> users (user_id));
 diesel::joinable!(sessions -> users (user_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
     cars,
+    driver_licenses,
     orders,
     sessions,
+    user_status_events,
     users,
 );
@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, warn};
+
+use crate::handlers::DbPool;
+use crate::infra::order_events::{self, OrderEvents};
+use crate::infra::services::{driver_licenses_service, sessions_service};
+use crate::infra::telegram;
+
+/// Periodically purges expired sessions until `shutdown` is cancelled.
+pub fn spawn_sessions_cleanup(
+    pool: DbPool,
+    interval: u64,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            match sessions_service::delete_expired(&pool).await {
+                Ok(deleted) => debug!("->> {:<12} - purged {} expired sessions", "JOB", deleted),
+                Err(err) => error!("->> {:<12} - failed to purge sessions: {}", "JOB", err),
+            }
+        }
+    })
+}
+
+/// Periodically warns users whose driver's license expires within `notice_days`, until
+/// `shutdown` is cancelled. Each license is only warned about once, until it's resubmitted,
+/// and given up on when Telegram can't reach its owner.
+pub fn spawn_license_expiry_notifications(
+    pool: DbPool,
+    interval: u64,
+    notice_days: i64,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval));
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+
+            let until = Utc::now().date_naive() + chrono::Duration::days(notice_days);
+
+            let expiring = match driver_licenses_service::get_expiring(&pool, until).await {
+                Ok(expiring) => expiring,
+                Err(err) => {
+                    error!("->> {:<12} - failed to get expiring licenses: {}", "JOB", err);
+                    continue;
+                }
+            };
+
+            for (license, telegram_id) in expiring {
+                let text = format!(
+                    "Your driver's license expires on {}. Please submit a renewed one to keep booking cars.",
+                    license.expires_at
+                );
+
+                match telegram::send_message(telegram_id, &text).await {
+                    Ok(()) => {}
+                    // Retrying won't help, the license is marked so it isn't tried on every run
+                    Err(err) if telegram::is_undeliverable(&err) => {
+                        warn!("->> {:<12} - can't notify user {}: {}", "JOB", license.user_id, err);
+                    }
+                    Err(err) => {
+                        error!("->> {:<12} - failed to notify user {}: {}", "JOB", license.user_id, err);
+                        continue;
+                    }
+                }
+
+                if let Err(err) = driver_licenses_service::mark_expiry_notified(&pool, license.id).await {
+                    error!("->> {:<12} - failed to mark license {}: {}", "JOB", license.id, err);
+                }
+            }
+        }
+    })
+}
+
+/// Relays the order events published by the database to `events` until `shutdown` is
+/// cancelled, reconnecting after `retry_delay` seconds whenever the connection is lost.
+pub fn spawn_order_events_listener(
+    database_url: String,
+    events: OrderEvents,
+    retry_delay: u64,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = order_events::listen(&database_url, &events, &shutdown).await {
+                error!("->> {:<12} - failed to listen to order events: {}", "JOB", err);
+            }
+
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_secs(retry_delay)) => {}
+            }
+        }
+    })
+}
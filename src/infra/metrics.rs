@@ -0,0 +1,74 @@
+use std::sync::OnceLock;
+
+use metrics::{counter, gauge};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+
+use crate::handlers::DbPool;
+use crate::infra::services::metrics_service::BusinessMetrics;
+
+pub const HTTP_REQUESTS_TOTAL: &str = "http_requests_total";
+pub const HTTP_REQUEST_DURATION_SECONDS: &str = "http_request_duration_seconds";
+
+const HTTP_REQUEST_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+const ORDER_STATUSES: &[&str] = &[
+    "awaits_confirmation",
+    "accepted",
+    "processing",
+    "finished",
+    "cancelled",
+];
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global recorder behind the `metrics` macros. Until it's installed
+/// everything recorded is dropped.
+pub fn install_recorder() {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(HTTP_REQUEST_DURATION_SECONDS.to_string()),
+            HTTP_REQUEST_DURATION_BUCKETS,
+        )
+        .expect("Failed to set the request duration buckets")
+        .install_recorder()
+        .expect("Failed to install the metrics recorder");
+
+    PROMETHEUS.set(handle).ok();
+}
+
+/// Pool gauges are read when scraped rather than tracked on every checkout.
+pub fn record_pool_state(pool: &DbPool) {
+    let state = pool.state();
+    let statistics = &state.statistics;
+
+    gauge!("db_pool_connections").set(state.connections);
+    gauge!("db_pool_idle_connections").set(state.idle_connections);
+    counter!("db_pool_gets_total", "kind" => "direct").absolute(statistics.get_direct);
+    counter!("db_pool_gets_total", "kind" => "waited").absolute(statistics.get_waited);
+    counter!("db_pool_gets_total", "kind" => "timed_out").absolute(statistics.get_timed_out);
+    gauge!("db_pool_get_wait_seconds_total").set(statistics.get_wait_time.as_secs_f64());
+}
+
+pub fn record_business_metrics(business_metrics: &BusinessMetrics) {
+    // Statuses without orders aren't returned, their gauges would keep the last count
+    for status in ORDER_STATUSES {
+        gauge!("car_sharing_orders", "status" => *status).set(0);
+    }
+
+    for (status, count) in &business_metrics.orders_by_status {
+        gauge!("car_sharing_orders", "status" => status.clone()).set(*count as f64);
+    }
+
+    gauge!("car_sharing_active_rentals").set(business_metrics.active_rentals as f64);
+    gauge!("car_sharing_paid_revenue").set(business_metrics.paid_revenue as f64);
+}
+
+/// All metrics in the Prometheus text format.
+pub fn render() -> String {
+    PROMETHEUS
+        .get()
+        .map(PrometheusHandle::render)
+        .unwrap_or_default()
+}
@@ -1,9 +1,7 @@
-use std::sync::{Arc, Mutex};
-
-use rand_chacha::ChaCha8Rng;
-
+pub mod csv;
 pub mod db;
+pub mod jobs;
+pub mod metrics;
+pub mod order_events;
 pub mod services;
-
-pub type Random = Arc<Mutex<ChaCha8Rng>>;
-
+pub mod telegram;
@@ -0,0 +1,158 @@
+use chrono::NaiveDateTime;
+use futures_util::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_postgres::{AsyncMessage, NoTls};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Postgres channel the `orders` trigger notifies.
+const CHANNEL: &str = "order_events";
+
+/// Events kept for subscribers that fall behind, older ones are dropped for them.
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderEventKind {
+    Created,
+    Cancelled,
+    Accepted,
+    Started,
+    Finished,
+    Paid,
+}
+
+impl OrderEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderEventKind::Created => "created",
+            OrderEventKind::Cancelled => "cancelled",
+            OrderEventKind::Accepted => "accepted",
+            OrderEventKind::Started => "started",
+            OrderEventKind::Finished => "finished",
+            OrderEventKind::Paid => "paid",
+        }
+    }
+}
+
+/// A change in the lifecycle of an order, as published by the database.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrderEvent {
+    pub event: OrderEventKind,
+    pub order_id: Uuid,
+    pub user_id: Uuid,
+    pub car_id: Uuid,
+    /// Status of the order after the change.
+    pub status: String,
+    pub paid: bool,
+    pub at: NaiveDateTime,
+}
+
+/// Fans the order events received by this instance out to its subscribers.
+#[derive(Clone)]
+pub struct OrderEvents {
+    sender: broadcast::Sender<OrderEvent>,
+    shutdown: CancellationToken,
+}
+
+impl OrderEvents {
+    pub fn new(shutdown: CancellationToken) -> Self {
+        let (sender, _) = broadcast::channel(CAPACITY);
+
+        OrderEvents { sender, shutdown }
+    }
+
+    fn publish(&self, event: OrderEvent) {
+        // Nobody listening isn't an error
+        let _ = self.sender.send(event);
+    }
+
+    /// Events published from now on. The stream ends on shutdown, so open streams
+    /// don't hold the server up.
+    pub fn subscribe(&self) -> impl Stream<Item = OrderEvent> + Send + 'static {
+        let receiver = self.sender.subscribe();
+
+        futures_util::stream::unfold(receiver, |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => return Some((event, receiver)),
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("->> {:<12} - subscriber missed {} order events", "EVENTS", skipped);
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+        .take_until(self.shutdown.clone().cancelled_owned())
+    }
+}
+
+/// Publishes the notifications of the `order_events` channel until `shutdown` is cancelled
+/// or the connection is lost.
+pub async fn listen(
+    database_url: &str,
+    events: &OrderEvents,
+    shutdown: &CancellationToken,
+) -> Result<(), tokio_postgres::Error> {
+    let (client, mut connection) = tokio_postgres::connect(database_url, NoTls).await?;
+    let mut messages = futures_util::stream::poll_fn(move |cx| connection.poll_message(cx));
+
+    // The connection has to be polled for LISTEN to complete
+    let statement = format!("LISTEN {}", CHANNEL);
+    let listen = client.batch_execute(&statement);
+    tokio::pin!(listen);
+    let mut listening = false;
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => return Ok(()),
+            res = &mut listen, if !listening => {
+                res?;
+                listening = true;
+                debug!("->> {:<12} - listening to {}", "EVENTS", CHANNEL);
+            }
+            message = messages.next() => match message {
+                Some(Ok(AsyncMessage::Notification(notification))) => {
+                    match serde_json::from_str::<OrderEvent>(notification.payload()) {
+                        Ok(event) => events.publish(event),
+                        Err(err) => warn!("->> {:<12} - invalid order event: {}", "EVENTS", err),
+                    }
+                }
+                Some(Ok(_)) => {}
+                Some(Err(err)) => return Err(err),
+                None => return Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribe() {
+        let shutdown = CancellationToken::new();
+        let events = OrderEvents::new(shutdown.clone());
+
+        let mut stream = Box::pin(events.subscribe());
+
+        let event = serde_json::from_str::<OrderEvent>(
+            r#"{"event": "paid", "order_id": "6f5f3312-ea84-4dd7-86e2-5460b1fbe341",
+                "user_id": "6f5f3312-ea84-4dd7-86e2-5460b1fbe341",
+                "car_id": "6f5f3312-ea84-4dd7-86e2-5460b1fbe341",
+                "status": "finished", "paid": true, "at": "2026-10-19T01:28:29.339212"}"#,
+        )
+        .unwrap();
+        events.publish(event);
+
+        assert_eq!(stream.next().await.map(|event| event.event), Some(OrderEventKind::Paid));
+
+        shutdown.cancel();
+        assert!(stream.next().await.is_none());
+    }
+}
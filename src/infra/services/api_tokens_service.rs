@@ -0,0 +1,270 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use diesel::{
+    ExpressionMethods, Insertable, JoinOnDsl, Queryable, QueryDsl, Selectable, SelectableHelper,
+};
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::handlers::api_tokens::ApiTokenResponse;
+use crate::handlers::auth::UserData;
+use crate::infra::db::schema::api_tokens as api_tokens_table;
+use crate::infra::db::schema::api_tokens::dsl::*;
+use crate::infra::db::schema::users;
+use crate::infra::services::users_service::UserDb;
+use crate::models::api_token::ApiToken;
+use crate::models::permission::Permission;
+use crate::models::role::Role;
+use crate::models::user_status::UserStatus;
+
+#[derive(Serialize, Queryable, Selectable)]
+#[diesel(table_name = api_tokens_table)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiTokenDb {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub token_hash: Vec<u8>,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_tokens_table)]
+pub struct NewApiTokenDb {
+    pub user_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: NaiveDateTime,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ApiTokensFilter {
+    pub user_id: Option<Uuid>,
+}
+
+/// How often `last_used_at` is refreshed, to avoid a write on every request.
+const LAST_USED_RESOLUTION: i64 = 60;
+
+/// Creates a token and returns it together with its only plain-text copy.
+pub async fn insert(pool: &DbPool, new_api_token: NewApiTokenDb) -> Result<(ApiTokenResponse, ApiToken)> {
+    debug!("->> {:<12} - insert", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let api_token = ApiToken::generate_new();
+
+    let res = diesel::insert_into(api_tokens)
+        .values((&new_api_token, token_hash.eq(api_token.into_database_value())))
+        .returning(ApiTokenDb::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok((ApiTokenResponse::from(res), api_token))
+}
+
+pub async fn get_all(pool: &DbPool, filter: ApiTokensFilter) -> Result<Vec<ApiTokenResponse>> {
+    debug!("->> {:<12} - get_all", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = api_tokens.into_boxed::<diesel::pg::Pg>();
+
+    if let Some(user_id_from_filter) = filter.user_id {
+        query = query.filter(user_id.eq(user_id_from_filter));
+    }
+
+    let res = query
+        .order(created_at.desc())
+        .select(ApiTokenDb::as_select())
+        .load::<ApiTokenDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let list_response = res.into_iter().map(ApiTokenResponse::from).collect();
+
+    Ok(list_response)
+}
+
+pub async fn revoke(pool: &DbPool, api_token_id: Uuid) -> Result<ApiTokenResponse> {
+    debug!("->> {:<12} - revoke", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = diesel::update(api_tokens.find(api_token_id))
+        .set(revoked_at.eq(Utc::now().naive_utc()))
+        .returning(ApiTokenDb::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(ApiTokenResponse::from(res))
+}
+
+/// Resolves a bearer token to its owner, limited to the token's scopes.
+pub async fn get_user_data_by_token(pool: &DbPool, token: &str) -> Result<UserData> {
+    debug!("->> {:<12} - get_user_data_by_token", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let now = Utc::now().naive_utc();
+
+    let (api_token_db, user_db) = api_tokens
+        .filter(token_hash.eq(ApiToken::from_header_value(token)?.into_database_value()))
+        .filter(expires_at.gt(now))
+        .filter(revoked_at.is_null())
+        .inner_join(users::table.on(users::id.eq(user_id)))
+        .select((ApiTokenDb::as_select(), UserDb::as_select()))
+        .first::<(ApiTokenDb, UserDb)>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let is_stale = api_token_db
+        .last_used_at
+        .is_none_or(|last_used| last_used < now - Duration::seconds(LAST_USED_RESOLUTION));
+
+    if is_stale {
+        diesel::update(api_tokens.find(api_token_db.id))
+            .set(last_used_at.eq(now))
+            .execute(conn)
+            .await
+            .map_err(CarSharingError::from)?;
+    }
+
+    Ok(token_user_data(&api_token_db, &user_db, now))
+}
+
+/// Checks a token again by its id, without counting it as used, e.g. for streams opened
+/// with it.
+pub async fn get_user_data(pool: &DbPool, api_token_id: Uuid) -> Result<UserData> {
+    debug!("->> {:<12} - get_user_data", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let now = Utc::now().naive_utc();
+
+    let (api_token_db, user_db) = api_tokens
+        .find(api_token_id)
+        .filter(expires_at.gt(now))
+        .filter(revoked_at.is_null())
+        .inner_join(users::table.on(users::id.eq(user_id)))
+        .select((ApiTokenDb::as_select(), UserDb::as_select()))
+        .first::<(ApiTokenDb, UserDb)>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(token_user_data(&api_token_db, &user_db, now))
+}
+
+fn token_user_data(api_token_db: &ApiTokenDb, user_db: &UserDb, now: NaiveDateTime) -> UserData {
+    UserData {
+        telegram_id: user_db.telegram_id,
+        user_id: user_db.id,
+        session_id: None,
+        api_token_id: Option::from(api_token_db.id),
+        scopes: Option::from(
+            api_token_db
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse::<Permission>().ok())
+                .collect::<Vec<Permission>>(),
+        ),
+        role: Role::from(user_db.role.as_str()),
+        status: UserStatus::effective(&user_db.status, user_db.status_until, now),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+    use crate::infra::services::users_service::{insert_or_update, NewUserDb};
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    async fn create_api_token(pool: &DbPool) -> (ApiTokenResponse, ApiToken) {
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        let new_api_token = NewApiTokenDb {
+            user_id: user_id_res,
+            created_by: Option::from(user_id_res),
+            name: "Accounting sync".to_string(),
+            scopes: vec![Permission::PaymentsRecord.as_str().to_string()],
+            expires_at: Utc::now().naive_utc() + Duration::days(1),
+        };
+
+        insert(pool, new_api_token)
+            .await
+            .expect("Failed to create an API token")
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_get_user_data_by_token() {
+        let pool = create_connection_pool().await;
+
+        let (api_token_res, api_token) = create_api_token(&pool).await;
+
+        let res = get_user_data_by_token(&pool, &api_token.into_header_value())
+            .await
+            .expect("Failed to get user by API token");
+
+        assert_eq!(res.user_id, api_token_res.user_id);
+        assert_eq!(res.scopes, Option::from(vec![Permission::PaymentsRecord]));
+        assert!(!res.has_permission(Permission::CarsDelete));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_02_revoke() {
+        let pool = create_connection_pool().await;
+
+        let (api_token_res, api_token) = create_api_token(&pool).await;
+
+        let res = get_user_data(&pool, api_token_res.id)
+            .await
+            .expect("Failed to get user by API token id");
+        assert_eq!(res.api_token_id, Option::from(api_token_res.id));
+
+        revoke(&pool, api_token_res.id)
+            .await
+            .expect("Failed to revoke an API token");
+
+        assert!(get_user_data_by_token(&pool, &api_token.into_header_value())
+            .await
+            .is_err());
+        assert!(get_user_data(&pool, api_token_res.id).await.is_err());
+    }
+}
@@ -1,17 +1,21 @@
 use chrono::NaiveDateTime;
 use diesel::{
-    AsChangeset, ExpressionMethods, Insertable, Queryable, QueryDsl, Selectable, SelectableHelper,
+    AsChangeset, BoolExpressionMethods, ExpressionMethods, Insertable, Queryable, QueryDsl,
+    Selectable, SelectableHelper,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
 use serde::{Deserialize, Serialize};
-use tracing::log::debug;
+use tracing::debug;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
 use crate::error::{CarSharingError, Result};
 use crate::handlers::{DbPool, get_conn};
-use crate::handlers::cars::{CarResponse, UpdateCarRequest};
+use crate::handlers::cars::{CarResponse, ImportReport, UpdateCarRequest};
 use crate::infra::db::schema::cars as cars_table;
 use crate::infra::db::schema::cars::dsl::*;
+use crate::models::FieldError;
 
 #[derive(Serialize, Queryable, Selectable)]
 #[diesel(table_name = cars_table)]
@@ -25,6 +29,7 @@ pub struct CarDb {
     pub photos: Option<Vec<Option<String>>>,
     pub status: String,
     pub created_at: NaiveDateTime,
+    pub license_plate: Option<String>,
 }
 
 #[derive(Deserialize, Insertable)]
@@ -35,9 +40,20 @@ pub struct NewCarDb {
     pub daily_rate: i32,
     pub weekly_rate: i32,
     pub photos: Option<Vec<Option<String>>>,
+    pub license_plate: Option<String>,
+    /// Left to the column's default when unset.
+    pub status: Option<String>,
 }
 
-#[derive(Deserialize)]
+/// A row of an imported fleet, matched to the stored car with its id when it has one,
+/// to the one with its license plate otherwise.
+pub struct ImportCarDb {
+    pub id: Option<Uuid>,
+    pub new_car: NewCarDb,
+}
+
+#[derive(Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct CarsFilter {
     status: Option<String>,
 }
@@ -50,6 +66,20 @@ struct UpdateCarChangeset {
     daily_rate: Option<i32>,
     weekly_rate: Option<i32>,
     status: Option<String>,
+    license_plate: Option<String>,
+}
+
+/// What an imported row overwrites on the car it matches, the status is only touched
+/// when the row sets one.
+#[derive(AsChangeset)]
+#[diesel(table_name = cars_table)]
+struct ImportCarChangeset<'a> {
+    name: &'a str,
+    hourly_rate: i32,
+    daily_rate: i32,
+    weekly_rate: i32,
+    photos: Option<Option<&'a Vec<Option<String>>>>,
+    status: Option<&'a str>,
 }
 
 pub async fn insert(pool: &DbPool, new_car: NewCarDb) -> Result<CarResponse> {
@@ -62,7 +92,7 @@ pub async fn insert(pool: &DbPool, new_car: NewCarDb) -> Result<CarResponse> {
         .values(&new_car)
         .get_result::<CarDb>(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(CarResponse::from(res))
 }
@@ -78,25 +108,29 @@ pub async fn get(pool: &DbPool, car_id: Uuid) -> Result<CarResponse> {
         .select(CarDb::as_select())
         .get_result(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(CarResponse::from(res))
 }
 
-pub async fn get_all(pool: &DbPool, _filter: CarsFilter) -> Result<Vec<CarResponse>> {
+pub async fn get_all(pool: &DbPool, filter: CarsFilter) -> Result<Vec<CarResponse>> {
     debug!("->> {:<12} - get_all", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
-    // Create a query to add filters later
-    let query = cars.into_boxed::<diesel::pg::Pg>();
+    // Create a query to add filters
+    let mut query = cars.into_boxed::<diesel::pg::Pg>();
+
+    if let Some(status_from_filter) = filter.status {
+        query = query.filter(status.eq(status_from_filter));
+    }
 
     let res = query
         .select(CarDb::as_select())
         .load::<CarDb>(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     // Make Vec<CarResponse> from res
     let list_response = res.into_iter().map(CarResponse::from).collect();
@@ -120,6 +154,7 @@ pub async fn update(
         daily_rate: updated_car.daily_rate,
         weekly_rate: updated_car.weekly_rate,
         status: updated_car.status,
+        license_plate: updated_car.license_plate,
     };
 
     let res = diesel::update(cars.find(car_id))
@@ -127,11 +162,119 @@ pub async fn update(
         .returning(CarDb::as_returning())
         .get_result(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(CarResponse::from(res))
 }
 
+/// Updates the cars matched by id, then by license plate, and creates the rows matching
+/// neither, in a single transaction so either all of them are saved or none. Rows with an
+/// id must match a car and can't take the plate of another one, otherwise nothing is saved
+/// and the report lists the errors. A dry run only counts what would be created and updated.
+pub async fn import(pool: &DbPool, rows: Vec<ImportCarDb>, dry_run: bool) -> Result<ImportReport> {
+    debug!("->> {:<12} - import", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = conn
+        .transaction::<ImportReport, CarSharingError, _>(|conn| {
+            async move {
+                let ids = rows.iter().filter_map(|row| row.id).collect::<Vec<Uuid>>();
+                let plates = rows
+                    .iter()
+                    .filter_map(|row| row.new_car.license_plate.clone())
+                    .collect::<Vec<String>>();
+
+                let stored = cars
+                    .filter(id.eq_any(&ids).or(license_plate.eq_any(&plates)))
+                    .select((id, license_plate))
+                    .load::<(Uuid, Option<String>)>(conn)
+                    .await?;
+
+                let mut errors = Vec::new();
+                let mut updated = 0;
+
+                for (index, row) in rows.iter().enumerate() {
+                    let plate_holder = stored
+                        .iter()
+                        .find(|(_, plate)| plate.is_some() && *plate == row.new_car.license_plate)
+                        .map(|(car_id, _)| *car_id);
+
+                    match row.id {
+                        Some(row_id) if !stored.iter().any(|(car_id, _)| *car_id == row_id) => {
+                            errors.push(FieldError::new(
+                                &format!("rows[{}].id", index + 1),
+                                "matches no car",
+                            ));
+                        }
+                        Some(row_id) if plate_holder.is_some_and(|car_id| car_id != row_id) => {
+                            errors.push(FieldError::new(
+                                &format!("rows[{}].license_plate", index + 1),
+                                "is already used by another car",
+                            ));
+                        }
+                        Some(_) => updated += 1,
+                        None if plate_holder.is_some() => updated += 1,
+                        None => {}
+                    }
+                }
+
+                if !errors.is_empty() {
+                    return Ok(ImportReport {
+                        dry_run,
+                        created: 0,
+                        updated: 0,
+                        errors,
+                    });
+                }
+
+                if !dry_run {
+                    for row in &rows {
+                        let new_car = &row.new_car;
+                        let changeset = ImportCarChangeset {
+                            name: &new_car.name,
+                            hourly_rate: new_car.hourly_rate,
+                            daily_rate: new_car.daily_rate,
+                            weekly_rate: new_car.weekly_rate,
+                            photos: Some(new_car.photos.as_ref()),
+                            status: new_car.status.as_deref(),
+                        };
+
+                        match row.id {
+                            Some(row_id) => {
+                                diesel::update(cars.find(row_id))
+                                    .set((&changeset, license_plate.eq(new_car.license_plate.as_deref())))
+                                    .execute(conn)
+                                    .await?
+                            }
+                            None => {
+                                diesel::insert_into(cars)
+                                    .values(new_car)
+                                    .on_conflict(license_plate)
+                                    .do_update()
+                                    .set(&changeset)
+                                    .execute(conn)
+                                    .await?
+                            }
+                        };
+                    }
+                }
+
+                Ok(ImportReport {
+                    dry_run,
+                    created: rows.len() - updated,
+                    updated,
+                    errors,
+                })
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    Ok(res)
+}
+
 pub async fn delete(pool: &DbPool, car_id: Uuid) -> Result<()> {
     debug!("->> {:<12} - delete", "INFRASTRUCTURE");
 
@@ -141,13 +284,14 @@ pub async fn delete(pool: &DbPool, car_id: Uuid) -> Result<()> {
     diesel::delete(cars.filter(id.eq(car_id)))
         .execute(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use diesel::TextExpressionMethods;
     use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
     use serial_test::serial;
 
@@ -167,7 +311,7 @@ mod tests {
 
         cars.first::<CarDb>(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .expect("Can't find a car")
     }
 
@@ -181,7 +325,7 @@ mod tests {
         diesel::delete(cars.filter(id.is_not_null()))
             .execute(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .unwrap();
     }
 
@@ -196,9 +340,11 @@ mod tests {
             daily_rate: 0,
             weekly_rate: 0,
             photos: Option::from(vec![Option::from("none".to_string())]),
+            license_plate: None,
+            status: None,
         };
 
-        assert!(!insert(&pool, new_car_db).await.is_err());
+        assert!(insert(&pool, new_car_db).await.is_ok());
     }
 
     #[tokio::test]
@@ -208,7 +354,7 @@ mod tests {
 
         let get_car_res = get_first_car(&pool).await;
 
-        assert!(!get(&pool, get_car_res.id).await.is_err());
+        assert!(get(&pool, get_car_res.id).await.is_ok());
     }
 
     #[tokio::test]
@@ -218,7 +364,7 @@ mod tests {
 
         let cars_filter = CarsFilter { status: None };
 
-        assert!(!get_all(&pool, cars_filter).await.is_err())
+        assert!(get_all(&pool, cars_filter).await.is_ok())
     }
 
     #[tokio::test]
@@ -234,6 +380,7 @@ mod tests {
             daily_rate: None,
             weekly_rate: None,
             status: None,
+            license_plate: None,
         };
 
         let res = update(&pool, get_car_res.id, update_car_req)
@@ -250,6 +397,128 @@ mod tests {
 
         let get_car_res = get_first_car(&pool).await;
 
-        assert!(!delete(&pool, get_car_res.id).await.is_err())
+        assert!(delete(&pool, get_car_res.id).await.is_ok())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_07_import() {
+        let pool = create_connection_pool().await;
+
+        let count_imported = || async {
+            let conn = &mut get_conn(&pool).await.unwrap();
+
+            cars.filter(license_plate.like("IMPORT-%"))
+                .count()
+                .get_result::<i64>(conn)
+                .await
+                .unwrap()
+        };
+
+        let count_unregistered = || async {
+            let conn = &mut get_conn(&pool).await.unwrap();
+
+            cars.filter(name.eq("Imported without plate"))
+                .count()
+                .get_result::<i64>(conn)
+                .await
+                .unwrap()
+        };
+
+        let new_car = |plate: Option<&str>| NewCarDb {
+            name: if plate.is_some() { "Imported" } else { "Imported without plate" }.to_string(),
+            hourly_rate: 20,
+            daily_rate: 150,
+            weekly_rate: 800,
+            photos: None,
+            license_plate: plate.map(str::to_string),
+            status: None,
+        };
+
+        let new_cars = || {
+            [Some("IMPORT-1"), Some("IMPORT-2"), None]
+                .map(|plate| ImportCarDb { id: None, new_car: new_car(plate) })
+                .into()
+        };
+
+        let report = import(&pool, new_cars(), true).await.expect("Failed to check cars");
+        assert_eq!((report.created, report.updated), (3, 0));
+        assert_eq!(count_imported().await, 0);
+
+        let report = import(&pool, new_cars(), false).await.expect("Failed to import cars");
+        assert_eq!((report.created, report.updated), (3, 0));
+
+        let report = import(&pool, new_cars(), false).await.expect("Failed to import cars");
+        assert_eq!((report.created, report.updated), (1, 2));
+        assert_eq!(count_imported().await, 2);
+        assert_eq!(count_unregistered().await, 2);
+
+        // Importing the stored cars back by id updates them all, even their plates
+        let stored = {
+            let conn = &mut get_conn(&pool).await.unwrap();
+
+            cars.filter(name.like("Imported%"))
+                .select(CarDb::as_select())
+                .load::<CarDb>(conn)
+                .await
+                .unwrap()
+        };
+        let exported = || {
+            stored
+                .iter()
+                .map(|car_db| {
+                    let plate = car_db
+                        .license_plate
+                        .as_ref()
+                        .map(|plate| plate.replace("IMPORT", "IMPORT-X"));
+
+                    ImportCarDb {
+                        id: Some(car_db.id),
+                        new_car: new_car(plate.as_deref()),
+                    }
+                })
+                .collect::<Vec<ImportCarDb>>()
+        };
+
+        let report = import(&pool, exported(), false).await.expect("Failed to import cars");
+        assert_eq!((report.created, report.updated), (0, 4));
+        assert_eq!(count_imported().await, 2);
+        assert_eq!(count_unregistered().await, 2);
+
+        let conn = &mut get_conn(&pool).await.unwrap();
+        let plates = cars
+            .filter(license_plate.like("IMPORT-X-%"))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .unwrap();
+        assert_eq!(plates, 2);
+
+        // Unknown ids and plates taken from other cars are reported, and nothing is saved
+        let mut rows = exported();
+        rows[0].id = Some(Uuid::new_v4());
+        rows[1].new_car.name = "Renamed".to_string();
+
+        let report = import(&pool, rows, false).await.expect("Failed to import cars");
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].field, "rows[1].id");
+
+        let mut rows = exported();
+        let plated = (0..rows.len())
+            .filter(|index| rows[*index].new_car.license_plate.is_some())
+            .collect::<Vec<usize>>();
+        rows[plated[0]].new_car.license_plate = rows[plated[1]].new_car.license_plate.clone();
+
+        let report = import(&pool, rows, true).await.expect("Failed to check cars");
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].field, format!("rows[{}].license_plate", plated[0] + 1));
+
+        let renamed = cars
+            .filter(name.eq("Renamed"))
+            .count()
+            .get_result::<i64>(conn)
+            .await
+            .unwrap();
+        assert_eq!(renamed, 0);
     }
 }
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDateTime};
+use diesel::QueryableByName;
+use diesel::sql_types::{BigInt, Bool, Integer, Nullable, Text, Timestamp, Uuid as SqlUuid};
+use diesel_async::RunQueryDsl;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::handlers::dashboard::{
+    DashboardOrder, DashboardResponse, DashboardRevenue, DashboardSection,
+};
+
+#[derive(QueryableByName)]
+pub struct DashboardOrderDb {
+    #[diesel(sql_type = Text)]
+    pub section: String,
+    #[diesel(sql_type = SqlUuid)]
+    pub order_id: Uuid,
+    #[diesel(sql_type = SqlUuid)]
+    pub car_id: Uuid,
+    #[diesel(sql_type = Text)]
+    pub car_name: String,
+    #[diesel(sql_type = SqlUuid)]
+    pub user_id: Uuid,
+    #[diesel(sql_type = Text)]
+    pub status: String,
+    #[diesel(sql_type = Bool)]
+    pub paid: bool,
+    #[diesel(sql_type = Timestamp)]
+    pub created_at: NaiveDateTime,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    pub start_rent_time: Option<NaiveDateTime>,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    pub end_rent_time: Option<NaiveDateTime>,
+    #[diesel(sql_type = Nullable<Timestamp>)]
+    pub due_at: Option<NaiveDateTime>,
+    #[diesel(sql_type = Nullable<Integer>)]
+    pub price: Option<i32>,
+}
+
+#[derive(QueryableByName)]
+struct DashboardCountsDb {
+    #[diesel(sql_type = BigInt)]
+    pending_confirmations: i64,
+    #[diesel(sql_type = BigInt)]
+    awaiting_pickup: i64,
+    #[diesel(sql_type = BigInt)]
+    rentals_in_progress: i64,
+    #[diesel(sql_type = BigInt)]
+    due_today: i64,
+    #[diesel(sql_type = BigInt)]
+    overdue: i64,
+    #[diesel(sql_type = BigInt)]
+    unpaid_finished: i64,
+    #[diesel(sql_type = BigInt)]
+    earned: i64,
+    #[diesel(sql_type = BigInt)]
+    paid: i64,
+}
+
+/// Orders in each section of the dashboard, with `$1` now and `$2` the start of tomorrow.
+const SECTIONS: [(&str, &str); 6] = [
+    ("pending_confirmations", "o.status = 'awaits_confirmation'"),
+    ("awaiting_pickup", "o.status = 'accepted'"),
+    ("rentals_in_progress", "o.status = 'processing'"),
+    ("due_today", "o.status = 'processing' AND o.due_at >= $1 AND o.due_at < $2"),
+    ("overdue", "o.status = 'processing' AND o.due_at < $1"),
+    ("unpaid_finished", "o.status = 'finished' AND NOT o.paid"),
+];
+
+/// How many orders need attention, the oldest `limit` of each section, and the revenue of
+/// the rentals finished on the day of `now`.
+pub async fn get_dashboard(
+    pool: &DbPool,
+    now: NaiveDateTime,
+    limit: i64,
+) -> Result<DashboardResponse> {
+    debug!("->> {:<12} - get_dashboard", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let today = now.date().and_time(Default::default());
+    let tomorrow = today + Duration::days(1);
+
+    let counts = SECTIONS
+        .iter()
+        .map(|(section, condition)| format!("COUNT(*) FILTER (WHERE {}) AS {}", condition, section))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let counts = diesel::sql_query(format!(
+        "SELECT {counts}, \
+                COALESCE(SUM(o.price) FILTER (WHERE {finished_today}), 0) AS earned, \
+                COALESCE(SUM(o.price) FILTER (WHERE {finished_today} AND o.paid), 0) AS paid \
+         FROM orders o",
+        finished_today = "o.status = 'finished' AND o.end_rent_time >= $3 AND o.end_rent_time < $2",
+    ))
+    .bind::<Timestamp, _>(now)
+    .bind::<Timestamp, _>(tomorrow)
+    .bind::<Timestamp, _>(today)
+    .get_result::<DashboardCountsDb>(conn)
+    .await
+    .map_err(CarSharingError::from)?;
+
+    let lists = SECTIONS
+        .iter()
+        .map(|(section, condition)| {
+            format!(
+                "(SELECT '{section}' AS section, o.id AS order_id, o.car_id, c.name AS car_name, \
+                         o.user_id, o.status, o.paid, o.created_at, o.start_rent_time, \
+                         o.end_rent_time, o.due_at, o.price \
+                  FROM orders o \
+                  JOIN cars c ON c.id = o.car_id \
+                  WHERE {condition} \
+                  ORDER BY o.created_at, o.id \
+                  LIMIT $3)"
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(" UNION ALL ");
+
+    let orders_db = diesel::sql_query(lists)
+        .bind::<Timestamp, _>(now)
+        .bind::<Timestamp, _>(tomorrow)
+        .bind::<BigInt, _>(limit)
+        .load::<DashboardOrderDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let mut orders = HashMap::<String, Vec<DashboardOrder>>::new();
+    for order_db in orders_db {
+        orders
+            .entry(order_db.section.clone())
+            .or_default()
+            .push(DashboardOrder::from(order_db));
+    }
+
+    let mut section = |count: i64, name: &str| DashboardSection {
+        count,
+        orders: orders.remove(name).unwrap_or_default(),
+    };
+
+    Ok(DashboardResponse {
+        pending_confirmations: section(counts.pending_confirmations, "pending_confirmations"),
+        awaiting_pickup: section(counts.awaiting_pickup, "awaiting_pickup"),
+        rentals_in_progress: section(counts.rentals_in_progress, "rentals_in_progress"),
+        due_today: section(counts.due_today, "due_today"),
+        overdue: section(counts.overdue, "overdue"),
+        unpaid_finished: section(counts.unpaid_finished, "unpaid_finished"),
+        revenue_today: DashboardRevenue {
+            earned: counts.earned,
+            paid: counts.paid,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::ExpressionMethods;
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+    use crate::infra::db::schema::orders;
+    use crate::infra::services::cars_service::{self, NewCarDb};
+    use crate::infra::services::users_service::{self, NewUserDb};
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_get_dashboard() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 800000002,
+            ..Default::default()
+        };
+
+        let user_id = users_service::insert_or_update(&pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        let car = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "Dashboard".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        // Late enough in the day for rentals to be due later today
+        let now = chrono::NaiveDate::from_ymd_opt(2001, 2, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let hours = Duration::hours;
+
+        let mut order_ids = Vec::new();
+        for (status, start, end, due, paid) in [
+            ("awaits_confirmation", None, None, None, false),
+            ("processing", Some(now - hours(3)), None, Some(now - hours(1)), false),
+            ("processing", Some(now - hours(3)), None, Some(now + hours(1)), false),
+            ("finished", Some(now - hours(5)), Some(now - hours(3)), None, false),
+            ("finished", Some(now - hours(5)), Some(now - hours(4)), None, true),
+            ("accepted", None, None, Some(now + hours(5)), false),
+        ] {
+            let conn = &mut get_conn(&pool).await.unwrap();
+
+            let order_id = diesel::insert_into(orders::table)
+                .values((
+                    orders::user_id.eq(user_id),
+                    orders::car_id.eq(car.id),
+                    orders::status.eq(status),
+                    orders::start_rent_time.eq(start),
+                    orders::end_rent_time.eq(end),
+                    orders::due_at.eq(due),
+                    orders::paid.eq(paid),
+                ))
+                .returning(orders::id)
+                .get_result::<Uuid>(conn)
+                .await
+                .expect("Failed to insert order");
+
+            order_ids.push(order_id);
+        }
+
+        // Other tests leave orders behind, so every one of them is listed
+        let res = get_dashboard(&pool, now, i64::MAX).await.unwrap();
+
+        let ids = |section: &DashboardSection| {
+            assert_eq!(section.count, section.orders.len() as i64);
+
+            section
+                .orders
+                .iter()
+                .map(|order| order.order_id)
+                .filter(|order_id| order_ids.contains(order_id))
+                .collect::<Vec<Uuid>>()
+        };
+
+        assert_eq!(ids(&res.pending_confirmations), vec![order_ids[0]]);
+        assert_eq!(ids(&res.awaiting_pickup), vec![order_ids[5]]);
+        assert_eq!(ids(&res.rentals_in_progress), vec![order_ids[1], order_ids[2]]);
+        assert_eq!(ids(&res.overdue), vec![order_ids[1]]);
+        assert_eq!(ids(&res.due_today), vec![order_ids[2]]);
+        assert_eq!(ids(&res.unpaid_finished), vec![order_ids[3]]);
+        let unpaid = res.unpaid_finished.orders.iter().find(|order| order.order_id == order_ids[3]);
+        assert_eq!(unpaid.and_then(|order| order.price), Some(40));
+        assert_eq!((res.revenue_today.earned, res.revenue_today.paid), (60, 20));
+
+        // Lists are cut to the limit, counts aren't
+        let limited = get_dashboard(&pool, now, 1).await.unwrap();
+        assert_eq!(limited.rentals_in_progress.count, res.rentals_in_progress.count);
+        assert_eq!(limited.rentals_in_progress.orders.len(), 1);
+        assert_eq!(
+            limited.rentals_in_progress.orders[0].order_id,
+            res.rentals_in_progress.orders[0].order_id
+        );
+    }
+}
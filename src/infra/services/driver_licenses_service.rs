@@ -0,0 +1,439 @@
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use diesel::{
+    ExpressionMethods, Insertable, JoinOnDsl, OptionalExtension, Queryable, QueryDsl, Selectable,
+    SelectableHelper,
+};
+use diesel::dsl::exists;
+use diesel::upsert::excluded;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use utoipa::IntoParams;
+use uuid::Uuid;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::handlers::licenses::DriverLicenseResponse;
+use crate::infra::db::schema::driver_licenses as driver_licenses_table;
+use crate::infra::db::schema::driver_licenses::dsl::*;
+use crate::infra::db::schema::users;
+use crate::models::license_status::LicenseStatus;
+
+#[derive(Serialize, Queryable, Selectable)]
+#[diesel(table_name = driver_licenses_table)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DriverLicenseDb {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub license_number: String,
+    pub categories: Vec<String>,
+    pub issued_at: NaiveDate,
+    pub expires_at: NaiveDate,
+    pub photos: Vec<String>,
+    pub status: String,
+    pub rejection_reason: Option<String>,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<NaiveDateTime>,
+    pub expiry_notified_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = driver_licenses_table)]
+pub struct NewDriverLicenseDb {
+    pub user_id: Uuid,
+    pub license_number: String,
+    pub categories: Vec<String>,
+    pub issued_at: NaiveDate,
+    pub expires_at: NaiveDate,
+    pub photos: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DriverLicensesFilter {
+    pub status: Option<LicenseStatus>,
+}
+
+/// Saves the user's license, replacing a previous one and sending it for review again.
+pub async fn submit(
+    pool: &DbPool,
+    new_driver_license: NewDriverLicenseDb,
+) -> Result<DriverLicenseResponse> {
+    debug!("->> {:<12} - submit", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = diesel::insert_into(driver_licenses)
+        .values(&new_driver_license)
+        .on_conflict(user_id)
+        .do_update()
+        .set((
+            license_number.eq(excluded(license_number)),
+            categories.eq(excluded(categories)),
+            issued_at.eq(excluded(issued_at)),
+            expires_at.eq(excluded(expires_at)),
+            photos.eq(excluded(photos)),
+            status.eq(LicenseStatus::Pending.as_str()),
+            rejection_reason.eq(None::<String>),
+            reviewed_by.eq(None::<Uuid>),
+            reviewed_at.eq(None::<NaiveDateTime>),
+            expiry_notified_at.eq(None::<NaiveDateTime>),
+        ))
+        .returning(DriverLicenseDb::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(DriverLicenseResponse::from(res))
+}
+
+pub async fn get(pool: &DbPool, driver_license_id: Uuid) -> Result<DriverLicenseResponse> {
+    debug!("->> {:<12} - get", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = driver_licenses
+        .find(driver_license_id)
+        .select(DriverLicenseDb::as_select())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(DriverLicenseResponse::from(res))
+}
+
+pub async fn get_by_user(pool: &DbPool, user_id_req: Uuid) -> Result<DriverLicenseResponse> {
+    debug!("->> {:<12} - get_by_user", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = driver_licenses
+        .filter(user_id.eq(user_id_req))
+        .select(DriverLicenseDb::as_select())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(DriverLicenseResponse::from(res))
+}
+
+pub async fn get_by_telegram_id(pool: &DbPool, telegram_id: i64) -> Result<DriverLicenseResponse> {
+    debug!("->> {:<12} - get_by_telegram_id", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = driver_licenses
+        .inner_join(users::table.on(users::id.eq(user_id)))
+        .filter(users::telegram_id.eq(telegram_id))
+        .select(DriverLicenseDb::as_select())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(DriverLicenseResponse::from(res))
+}
+
+/// Status of the user's license in effect on `today`, `None` when they never submitted one.
+pub async fn get_status_by_user(
+    pool: &DbPool,
+    user_id_req: Uuid,
+    today: NaiveDate,
+) -> Result<Option<LicenseStatus>> {
+    debug!("->> {:<12} - get_status_by_user", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = driver_licenses
+        .filter(user_id.eq(user_id_req))
+        .select((status, expires_at))
+        .get_result::<(String, NaiveDate)>(conn)
+        .await
+        .optional()
+        .map_err(CarSharingError::from)?;
+
+    Ok(res.map(|(status_db, expires_at_db)| {
+        LicenseStatus::effective(&status_db, expires_at_db, today)
+    }))
+}
+
+pub async fn get_all(
+    pool: &DbPool,
+    filter: DriverLicensesFilter,
+) -> Result<Vec<DriverLicenseResponse>> {
+    debug!("->> {:<12} - get_all", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let today = Utc::now().date_naive();
+
+    let mut query = driver_licenses.into_boxed::<diesel::pg::Pg>();
+
+    // Expiry isn't stored, so approved and expired licenses are told apart by date
+    query = match filter.status {
+        Some(LicenseStatus::Approved) => query
+            .filter(status.eq(LicenseStatus::Approved.as_str()))
+            .filter(expires_at.ge(today)),
+        Some(LicenseStatus::Expired) => query
+            .filter(status.eq(LicenseStatus::Approved.as_str()))
+            .filter(expires_at.lt(today)),
+        Some(status_from_filter) => query.filter(status.eq(status_from_filter.as_str())),
+        None => query,
+    };
+
+    let res = query
+        .order(created_at.desc())
+        .select(DriverLicenseDb::as_select())
+        .load::<DriverLicenseDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let list_response = res.into_iter().map(DriverLicenseResponse::from).collect();
+
+    Ok(list_response)
+}
+
+/// Approves or rejects a pending license, the reason is shown to the user on rejection.
+/// Reviewers can't review their own license, `None` reviews on behalf of the operator.
+pub async fn review(
+    pool: &DbPool,
+    driver_license_id: Uuid,
+    reviewer_id: Option<Uuid>,
+    new_status: LicenseStatus,
+    reason: Option<String>,
+) -> Result<DriverLicenseResponse> {
+    debug!("->> {:<12} - review", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = conn
+        .transaction::<DriverLicenseDb, CarSharingError, _>(|conn| {
+            async move {
+                // Locked so concurrent reviews can't both see the license pending
+                let (owner_id, current_status) = driver_licenses
+                    .find(driver_license_id)
+                    .select((user_id, status))
+                    .for_update()
+                    .get_result::<(Uuid, String)>(conn)
+                    .await?;
+
+                if reviewer_id == Some(owner_id) {
+                    return Err(CarSharingError::OwnLicense);
+                }
+                if current_status != LicenseStatus::Pending.as_str() {
+                    return Err(CarSharingError::LicenseNotPending);
+                }
+
+                let license_db = diesel::update(driver_licenses.find(driver_license_id))
+                    .set((
+                        status.eq(new_status.as_str()),
+                        rejection_reason.eq(reason),
+                        reviewed_by.eq(reviewer_id),
+                        reviewed_at.eq(Utc::now().naive_utc()),
+                    ))
+                    .returning(DriverLicenseDb::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                Ok(license_db)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    Ok(DriverLicenseResponse::from(res))
+}
+
+/// Whether the user has an approved license that hasn't expired by `today`.
+pub async fn has_valid_license(pool: &DbPool, user_id_req: Uuid, today: NaiveDate) -> Result<bool> {
+    debug!("->> {:<12} - has_valid_license", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::select(exists(
+        driver_licenses
+            .filter(user_id.eq(user_id_req))
+            .filter(status.eq(LicenseStatus::Approved.as_str()))
+            .filter(expires_at.ge(today)),
+    ))
+    .get_result(conn)
+    .await
+    .map_err(CarSharingError::from)
+}
+
+/// Approved licenses expiring by `until` whose owners haven't been notified yet,
+/// together with the owners' Telegram ids.
+pub async fn get_expiring(pool: &DbPool, until: NaiveDate) -> Result<Vec<(DriverLicenseDb, i64)>> {
+    debug!("->> {:<12} - get_expiring", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    driver_licenses
+        .filter(status.eq(LicenseStatus::Approved.as_str()))
+        .filter(expires_at.le(until))
+        .filter(expiry_notified_at.is_null())
+        .inner_join(users::table.on(users::id.eq(user_id)))
+        .select((DriverLicenseDb::as_select(), users::telegram_id))
+        .load::<(DriverLicenseDb, i64)>(conn)
+        .await
+        .map_err(CarSharingError::from)
+}
+
+pub async fn mark_expiry_notified(pool: &DbPool, driver_license_id: Uuid) -> Result<()> {
+    debug!("->> {:<12} - mark_expiry_notified", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::update(driver_licenses.find(driver_license_id))
+        .set(expiry_notified_at.eq(Utc::now().naive_utc()))
+        .execute(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+    use crate::infra::services::users_service::{insert_or_update, NewUserDb};
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    async fn submit_license(pool: &DbPool, expires_in_days: i64) -> DriverLicenseResponse {
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        let today = Utc::now().date_naive();
+
+        let new_driver_license = NewDriverLicenseDb {
+            user_id: user_id_res,
+            license_number: "99 01 123456".to_string(),
+            categories: vec!["B".to_string()],
+            issued_at: today - Duration::days(365),
+            expires_at: today + Duration::days(expires_in_days),
+            photos: vec!["https://example.com/license.jpg".to_string()],
+        };
+
+        submit(pool, new_driver_license)
+            .await
+            .expect("Failed to submit a driver license")
+    }
+
+    async fn insert_reviewer(pool: &DbPool) -> Uuid {
+        let new_user = NewUserDb {
+            telegram_id: 800000003,
+            ..Default::default()
+        };
+
+        insert_or_update(pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID")
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_has_valid_license() {
+        let pool = create_connection_pool().await;
+
+        let license = submit_license(&pool, 30).await;
+        let today = Utc::now().date_naive();
+
+        assert_eq!(license.status, LicenseStatus::Pending);
+        assert_eq!(get_by_telegram_id(&pool, 443621429).await.unwrap().id, license.id);
+        assert!(!has_valid_license(&pool, license.user_id, today).await.unwrap());
+        assert_eq!(
+            get_status_by_user(&pool, license.user_id, today).await.unwrap(),
+            Some(LicenseStatus::Pending)
+        );
+
+        let reviewer_id = insert_reviewer(&pool).await;
+
+        review(&pool, license.id, Some(reviewer_id), LicenseStatus::Approved, None)
+            .await
+            .expect("Failed to approve a driver license");
+
+        assert!(has_valid_license(&pool, license.user_id, today).await.unwrap());
+        assert!(!has_valid_license(&pool, license.user_id, today + Duration::days(31))
+            .await
+            .unwrap());
+        assert_eq!(
+            get_status_by_user(&pool, license.user_id, today + Duration::days(31)).await.unwrap(),
+            Some(LicenseStatus::Expired)
+        );
+        assert_eq!(get_status_by_user(&pool, Uuid::new_v4(), today).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_02_get_expiring() {
+        let pool = create_connection_pool().await;
+
+        let license = submit_license(&pool, 10).await;
+        let today = Utc::now().date_naive();
+
+        review(&pool, license.id, None, LicenseStatus::Approved, None)
+            .await
+            .expect("Failed to approve a driver license");
+
+        let expiring = get_expiring(&pool, today + Duration::days(30)).await.unwrap();
+        assert!(expiring.iter().any(|(license_db, _)| license_db.id == license.id));
+
+        mark_expiry_notified(&pool, license.id).await.unwrap();
+
+        let expiring = get_expiring(&pool, today + Duration::days(30)).await.unwrap();
+        assert!(!expiring.iter().any(|(license_db, _)| license_db.id == license.id));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_03_review_pending_only() {
+        let pool = create_connection_pool().await;
+
+        let license = submit_license(&pool, 30).await;
+        let reviewer_id = insert_reviewer(&pool).await;
+
+        let own_review =
+            review(&pool, license.id, Some(license.user_id), LicenseStatus::Approved, None).await;
+        assert!(matches!(own_review, Err(CarSharingError::OwnLicense)));
+
+        let reason = Option::from("Unreadable photo".to_string());
+        review(&pool, license.id, Some(reviewer_id), LicenseStatus::Rejected, reason)
+            .await
+            .expect("Failed to reject a driver license");
+
+        let second_review =
+            review(&pool, license.id, Some(reviewer_id), LicenseStatus::Approved, None).await;
+        assert!(matches!(second_review, Err(CarSharingError::LicenseNotPending)));
+        assert_eq!(get(&pool, license.id).await.unwrap().status, LicenseStatus::Rejected);
+    }
+}
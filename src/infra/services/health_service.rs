@@ -0,0 +1,91 @@
+use diesel::migration::MigrationSource;
+use diesel::pg::Pg;
+use diesel::QueryableByName;
+use diesel::sql_types::Text;
+use diesel_async::RunQueryDsl;
+use tracing::debug;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::infra::db::MIGRATIONS;
+
+#[derive(QueryableByName)]
+struct AppliedMigrationDb {
+    #[diesel(sql_type = Text)]
+    version: String,
+}
+
+pub async fn check_database(pool: &DbPool) -> Result<()> {
+    debug!("->> {:<12} - check_database", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::sql_query("SELECT 1")
+        .execute(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(())
+}
+
+/// Versions of the embedded migrations the database hasn't run yet.
+pub async fn get_pending_migrations(pool: &DbPool) -> Result<Vec<String>> {
+    debug!("->> {:<12} - get_pending_migrations", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let applied = diesel::sql_query("SELECT version FROM __diesel_schema_migrations")
+        .load::<AppliedMigrationDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?
+        .into_iter()
+        .map(|migration| migration.version)
+        .collect::<Vec<String>>();
+
+    Ok(pending_migrations(&applied))
+}
+
+fn pending_migrations(applied: &[String]) -> Vec<String> {
+    MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .expect("Embedded migrations are always readable")
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .filter(|version| !applied.contains(version))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_check_database() {
+        let pool = create_connection_pool().await;
+
+        assert!(check_database(&pool).await.is_ok());
+    }
+
+    #[test]
+    fn test_02_pending_migrations() {
+        let all = pending_migrations(&[]);
+
+        assert!(all.contains(&"20261019120000".to_string()));
+        assert!(pending_migrations(&all).is_empty());
+        assert_eq!(all.len() - 1, pending_migrations(&all[..1]).len());
+    }
+}
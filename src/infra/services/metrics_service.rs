@@ -0,0 +1,116 @@
+use diesel::{ExpressionMethods, QueryDsl};
+use diesel::dsl::count_star;
+use diesel_async::RunQueryDsl;
+use tracing::debug;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::infra::db::schema::orders;
+
+pub struct BusinessMetrics {
+    pub orders_by_status: Vec<(String, i64)>,
+    pub active_rentals: i64,
+    pub paid_revenue: i64,
+}
+
+pub async fn get_business_metrics(pool: &DbPool) -> Result<BusinessMetrics> {
+    debug!("->> {:<12} - get_business_metrics", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let orders_by_status = orders::table
+        .group_by(orders::status)
+        .select((orders::status, count_star()))
+        .load::<(String, i64)>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let active_rentals = orders::table
+        .filter(orders::status.eq("processing"))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let paid_revenue = orders::table
+        .filter(orders::paid.eq(true))
+        .select(diesel::dsl::sum(orders::price))
+        .get_result::<Option<i64>>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(BusinessMetrics {
+        orders_by_status,
+        active_rentals,
+        paid_revenue: paid_revenue.unwrap_or(0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+    use crate::infra::db::rental_cost;
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    async fn cost(pool: &DbPool, start: &str, end: &str) -> Option<i32> {
+        let conn = &mut get_conn(pool).await.unwrap();
+
+        let start = NaiveDateTime::parse_from_str(start, "%Y-%m-%d %H:%M").unwrap();
+        let end = NaiveDateTime::parse_from_str(end, "%Y-%m-%d %H:%M").unwrap();
+
+        diesel::select(rental_cost(
+            start,
+            end,
+            20,
+            150,
+            800,
+        ))
+        .get_result(conn)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_rental_cost() {
+        let pool = create_connection_pool().await;
+
+        // Started hours are billed
+        assert_eq!(Some(60), cost(&pool, "2026-01-01 00:00", "2026-01-01 02:30").await);
+        // 23 hours would cost more than a day
+        assert_eq!(Some(300), cost(&pool, "2026-01-01 00:00", "2026-01-02 23:00").await);
+        // 6 days would cost more than a week
+        assert_eq!(Some(800), cost(&pool, "2026-01-01 00:00", "2026-01-06 22:00").await);
+        assert_eq!(Some(970), cost(&pool, "2026-01-01 00:00", "2026-01-09 01:00").await);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_02_get_business_metrics() {
+        let pool = create_connection_pool().await;
+
+        let res = get_business_metrics(&pool).await.unwrap();
+
+        let processing = res
+            .orders_by_status
+            .iter()
+            .find(|(status, _)| status == "processing")
+            .map_or(0, |(_, count)| *count);
+
+        assert_eq!(processing, res.active_rentals);
+        assert!(res.paid_revenue >= 0);
+    }
+}
@@ -1,4 +1,10 @@
+pub mod api_tokens_service;
 pub mod cars_service;
+pub mod driver_licenses_service;
 pub mod users_service;
 pub mod orders_service;
 pub mod sessions_service;
+pub mod metrics_service;
+pub mod health_service;
+pub mod reports_service;
+pub mod dashboard_service;
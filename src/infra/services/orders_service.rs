@@ -2,9 +2,11 @@ use chrono::NaiveDateTime;
 use diesel::{
     AsChangeset, ExpressionMethods, Insertable, Queryable, QueryDsl, Selectable, SelectableHelper,
 };
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
 use serde::{Deserialize, Serialize};
-use tracing::log::debug;
+use tracing::debug;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
 use crate::error::{CarSharingError, Result};
@@ -26,6 +28,8 @@ pub struct OrderDb {
     pub paid: bool,
     pub created_at: NaiveDateTime,
     pub updated_at: Option<NaiveDateTime>,
+    pub due_at: Option<NaiveDateTime>,
+    pub price: Option<i32>,
 }
 
 #[derive(Deserialize, Insertable)]
@@ -33,9 +37,11 @@ pub struct OrderDb {
 pub struct NewOrderDb {
     pub user_id: Uuid,
     pub car_id: Uuid,
+    pub due_at: Option<NaiveDateTime>,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct OrdersFilter {
     pub user_id: Option<Uuid>,
 }
@@ -59,7 +65,7 @@ pub async fn insert(pool: &DbPool, new_order_db: NewOrderDb) -> Result<OrderResp
         .values(&new_order_db)
         .get_result::<OrderDb>(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(OrderResponse::from(res))
 }
@@ -74,7 +80,7 @@ pub async fn get(pool: &DbPool, order_id: Uuid) -> Result<OrderResponse> {
         .select(OrderDb::as_select())
         .get_result(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(OrderResponse::from(res))
 }
@@ -94,7 +100,7 @@ pub async fn get_all(pool: &DbPool, filter: OrdersFilter) -> Result<Vec<OrderRes
         .select(OrderDb::as_select())
         .load::<OrderDb>(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     let list_response = res
         .into_iter()
@@ -126,7 +132,51 @@ pub async fn update(
         .returning(OrderDb::as_returning())
         .get_result(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
+
+    Ok(OrderResponse::from(res))
+}
+
+/// Sets when the car of an order is due back, as long as the order is still open.
+pub async fn set_due_at(
+    pool: &DbPool,
+    order_id: Uuid,
+    new_due_at: NaiveDateTime,
+) -> Result<OrderResponse> {
+    debug!("->> {:<12} - set_due_at", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = conn
+        .transaction::<OrderDb, CarSharingError, _>(|conn| {
+            async move {
+                // Locked so the order can't be finished or cancelled meanwhile
+                let current_status = orders
+                    .find(order_id)
+                    .select(status)
+                    .for_update()
+                    .get_result::<String>(conn)
+                    .await?;
+
+                if current_status == "finished" || current_status == "cancelled" {
+                    return Err(CarSharingError::OrderClosed);
+                }
+
+                let order_db = diesel::update(orders.find(order_id))
+                    .set((
+                        due_at.eq(new_due_at),
+                        updated_at.eq(chrono::Utc::now().naive_utc()),
+                    ))
+                    .returning(OrderDb::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                Ok(order_db)
+            }
+            .scope_boxed()
+        })
+        .await?;
 
     Ok(OrderResponse::from(res))
 }
@@ -139,11 +189,32 @@ pub async fn delete(pool: &DbPool, order_id: Uuid) -> Result<String> {
     diesel::delete(orders.filter(id.eq(order_id)))
         .execute(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok("Order was successfully deleted!".to_string())
 }
 
+/// Cancels orders still awaiting confirmation that were placed before `created_before`.
+pub async fn expire_unconfirmed(pool: &DbPool, created_before: NaiveDateTime) -> Result<usize> {
+    debug!("->> {:<12} - expire_unconfirmed", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::update(
+        orders
+            .filter(status.eq("awaits_confirmation"))
+            .filter(created_at.lt(created_before)),
+    )
+    .set((
+        status.eq("cancelled"),
+        updated_at.eq(Some(chrono::Utc::now().naive_utc())),
+    ))
+    .execute(conn)
+    .await
+    .map_err(CarSharingError::from)
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
@@ -152,8 +223,9 @@ mod tests {
 
     use crate::config::config;
     use crate::infra::services::cars_service;
+    use crate::handlers::cars::UpdateCarRequest;
     use crate::infra::services::cars_service::NewCarDb;
-    use crate::infra::services::users_service::insert_if_not_exists;
+    use crate::infra::services::users_service::{insert_or_update, NewUserDb};
 
     use super::*;
 
@@ -170,7 +242,7 @@ mod tests {
         orders
             .first::<OrderDb>(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .expect("Can't find a session")
     }
 
@@ -184,7 +256,7 @@ mod tests {
         diesel::delete(orders.filter(id.is_not_null()))
             .execute(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .unwrap();
     }
 
@@ -193,7 +265,12 @@ mod tests {
     async fn test_02_insert() {
         let pool = create_connection_pool().await;
 
-        let user_id_res = insert_if_not_exists(&pool, 443621429)
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(&pool, new_user)
             .await
             .expect("Failed to insert user or retrieve existing ID");
 
@@ -203,6 +280,8 @@ mod tests {
             daily_rate: 0,
             weekly_rate: 0,
             photos: Option::from(vec![Option::from("none".to_string())]),
+            license_plate: None,
+            status: None,
         };
 
         let new_car_res = cars_service::insert(&pool, new_car_db)
@@ -212,9 +291,10 @@ mod tests {
         let new_order = NewOrderDb {
             user_id: user_id_res,
             car_id: new_car_res.id,
+            due_at: None,
         };
 
-        assert!(!insert(&pool, new_order).await.is_err())
+        assert!(insert(&pool, new_order).await.is_ok())
     }
 
     #[tokio::test]
@@ -224,7 +304,7 @@ mod tests {
 
         let get_order_res = get_first_order(&pool).await;
 
-        assert!(!get(&pool, get_order_res.id).await.is_err())
+        assert!(get(&pool, get_order_res.id).await.is_ok())
     }
 
     #[tokio::test]
@@ -234,7 +314,7 @@ mod tests {
 
         let orders_filter = OrdersFilter { user_id: None };
 
-        assert!(!get_all(&pool, orders_filter).await.is_err())
+        assert!(get_all(&pool, orders_filter).await.is_ok())
     }
 
     #[tokio::test]
@@ -268,6 +348,206 @@ mod tests {
 
         let get_order_res = get_first_order(&pool).await;
 
-        assert!(!delete(&pool, get_order_res.id).await.is_err())
+        assert!(delete(&pool, get_order_res.id).await.is_ok())
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_07_expire_unconfirmed() {
+        let pool = create_connection_pool().await;
+
+        let user_id_res = insert_or_update(
+            &pool,
+            NewUserDb {
+                telegram_id: 443621429,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let new_car_res = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "".to_string(),
+                hourly_rate: 0,
+                daily_rate: 0,
+                weekly_rate: 0,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        let order = insert(
+            &pool,
+            NewOrderDb {
+                user_id: user_id_res,
+                car_id: new_car_res.id,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("Failed to insert order");
+
+        // Orders placed after the cutoff are kept
+        let expired = expire_unconfirmed(&pool, order.created_at - chrono::Duration::seconds(1))
+            .await
+            .expect("Failed to expire orders");
+        assert_eq!(0, expired);
+
+        let expired = expire_unconfirmed(&pool, Utc::now().naive_utc())
+            .await
+            .expect("Failed to expire orders");
+        assert!(expired >= 1);
+
+        let res = get(&pool, order.id).await.expect("Failed to get order");
+        assert_eq!("cancelled".to_string(), res.status);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_08_price_fixed_when_finished() {
+        let pool = create_connection_pool().await;
+
+        let user_id_res = insert_or_update(
+            &pool,
+            NewUserDb {
+                telegram_id: 443621429,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let new_car_res = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "Priced".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        let order = insert(
+            &pool,
+            NewOrderDb {
+                user_id: user_id_res,
+                car_id: new_car_res.id,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("Failed to insert order");
+
+        let now = Utc::now().naive_utc();
+
+        let finish_order_req = UpdateOrderDb {
+            start_rent_time: Option::from(now - chrono::Duration::minutes(150)),
+            end_rent_time: Option::from(now),
+            status: Option::from("finished".to_string()),
+            paid: None,
+            updated_at: Option::from(now),
+        };
+
+        let res = update(&pool, order.id, finish_order_req)
+            .await
+            .expect("Failed to finish an order");
+        assert_eq!(Some(60), res.price);
+
+        let new_rates = UpdateCarRequest {
+            name: None,
+            hourly_rate: Option::from(100),
+            daily_rate: Option::from(500),
+            weekly_rate: Option::from(2000),
+            status: None,
+            license_plate: None,
+        };
+
+        cars_service::update(&pool, new_car_res.id, new_rates)
+            .await
+            .expect("Failed to update car");
+
+        let res = get(&pool, order.id).await.expect("Failed to get order");
+        assert_eq!(Some(60), res.price);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_09_set_due_at() {
+        let pool = create_connection_pool().await;
+
+        let user_id_res = insert_or_update(
+            &pool,
+            NewUserDb {
+                telegram_id: 443621429,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let new_car_res = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "Due".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        let order = insert(
+            &pool,
+            NewOrderDb {
+                user_id: user_id_res,
+                car_id: new_car_res.id,
+                due_at: None,
+            },
+        )
+        .await
+        .expect("Failed to insert order");
+
+        let now = Utc::now().naive_utc();
+        let tomorrow = now.date().succ_opt().unwrap().and_hms_opt(12, 0, 0).unwrap();
+
+        let res = set_due_at(&pool, order.id, tomorrow)
+            .await
+            .expect("Failed to set when the order is due");
+        assert_eq!(Some(tomorrow), res.due_at);
+
+        let cancel_order_req = UpdateOrderDb {
+            start_rent_time: None,
+            end_rent_time: None,
+            status: Option::from("cancelled".to_string()),
+            paid: None,
+            updated_at: Option::from(now),
+        };
+
+        update(&pool, order.id, cancel_order_req)
+            .await
+            .expect("Failed to cancel an order");
+
+        assert!(matches!(
+            set_due_at(&pool, order.id, tomorrow).await,
+            Err(CarSharingError::OrderClosed)
+        ));
+        assert!(matches!(
+            set_due_at(&pool, Uuid::new_v4(), tomorrow).await,
+            Err(CarSharingError::DatabaseNotFound)
+        ));
     }
 }
@@ -0,0 +1,309 @@
+use chrono::NaiveDateTime;
+use diesel::QueryableByName;
+use diesel::sql_types::{BigInt, Double, Nullable, Text, Timestamp, Uuid as SqlUuid};
+use diesel_async::RunQueryDsl;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::error::{CarSharingError, Result};
+use crate::handlers::{DbPool, get_conn};
+use crate::handlers::reports::{RevenueGrouping, RevenueReportRow, UtilizationReportRow};
+
+#[derive(QueryableByName)]
+pub struct RevenueRowDb {
+    #[diesel(sql_type = Text)]
+    pub key: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub label: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    pub orders: i64,
+    #[diesel(sql_type = Double)]
+    pub rented_hours: f64,
+    #[diesel(sql_type = BigInt)]
+    pub revenue: i64,
+    #[diesel(sql_type = BigInt)]
+    pub paid_revenue: i64,
+}
+
+#[derive(QueryableByName)]
+pub struct UtilizationRowDb {
+    #[diesel(sql_type = SqlUuid)]
+    pub car_id: Uuid,
+    #[diesel(sql_type = Text)]
+    pub name: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    pub license_plate: Option<String>,
+    #[diesel(sql_type = BigInt)]
+    pub orders: i64,
+    #[diesel(sql_type = Double)]
+    pub rented_hours: f64,
+    #[diesel(sql_type = Double)]
+    pub utilization: f64,
+}
+
+/// Orders belong to the time their rental started, or to their creation when it never did.
+const ORDER_TIME: &str = "COALESCE(o.start_rent_time, o.created_at)";
+
+/// Key and label of each `RevenueGrouping`, fixed SQL rather than anything from the request.
+fn grouping_columns(grouping: RevenueGrouping) -> (String, &'static str) {
+    let period = |unit: &str| format!("to_char(date_trunc('{}', {}), 'YYYY-MM-DD')", unit, ORDER_TIME);
+
+    match grouping {
+        RevenueGrouping::Car => ("c.id::TEXT".to_string(), "c.name"),
+        RevenueGrouping::Day => (period("day"), "NULL::TEXT"),
+        RevenueGrouping::Week => (period("week"), "NULL::TEXT"),
+        RevenueGrouping::Month => (period("month"), "NULL::TEXT"),
+        RevenueGrouping::Status => ("o.status".to_string(), "NULL::TEXT"),
+    }
+}
+
+/// Orders, rental hours and prices of the orders placed between `from` and `to`
+/// (excluded). Only finished rentals have a price.
+pub async fn get_revenue(
+    pool: &DbPool,
+    grouping: RevenueGrouping,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<RevenueReportRow>> {
+    debug!("->> {:<12} - get_revenue", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let (key, label) = grouping_columns(grouping);
+    let query = format!(
+        "SELECT {key} AS key, {label} AS label, COUNT(*) AS orders, \
+                ROUND(COALESCE(SUM(EXTRACT(EPOCH FROM o.end_rent_time - o.start_rent_time)), 0)::NUMERIC / 3600, 2)::FLOAT8 AS rented_hours, \
+                COALESCE(SUM(o.price), 0) AS revenue, \
+                COALESCE(SUM(o.price) FILTER (WHERE o.paid), 0) AS paid_revenue \
+         FROM orders o \
+         JOIN cars c ON c.id = o.car_id \
+         WHERE {order_time} >= $1 AND {order_time} < $2 \
+         GROUP BY 1, 2 \
+         ORDER BY 2, 1",
+        order_time = ORDER_TIME,
+    );
+
+    let res = diesel::sql_query(query)
+        .bind::<Timestamp, _>(from)
+        .bind::<Timestamp, _>(to)
+        .load::<RevenueRowDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(res.into_iter().map(RevenueReportRow::from).collect())
+}
+
+/// Share of the time between `from` and `to` each car spent rented. Neither the period
+/// nor the rentals in progress extend past now, so they only count the time elapsed.
+pub async fn get_utilization(
+    pool: &DbPool,
+    from: NaiveDateTime,
+    to: NaiveDateTime,
+) -> Result<Vec<UtilizationReportRow>> {
+    debug!("->> {:<12} - get_utilization", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = diesel::sql_query(
+        "WITH period AS ( \
+             SELECT $1 AS since, GREATEST($1, LEAST($2, NOW() AT TIME ZONE 'UTC')) AS until \
+         ), rented AS ( \
+             SELECT c.id, c.name, c.license_plate, COUNT(o.id) AS orders, \
+                    COALESCE(SUM(EXTRACT(EPOCH FROM \
+                        LEAST(COALESCE(o.end_rent_time, p.until), p.until) - GREATEST(o.start_rent_time, p.since) \
+                    )), 0)::NUMERIC AS seconds \
+             FROM cars c \
+             CROSS JOIN period p \
+             LEFT JOIN orders o ON o.car_id = c.id \
+                 AND o.start_rent_time < p.until \
+                 AND COALESCE(o.end_rent_time, p.until) > p.since \
+             GROUP BY c.id \
+         ) \
+         SELECT id AS car_id, name, license_plate, orders, \
+                ROUND(seconds / 3600, 2)::FLOAT8 AS rented_hours, \
+                COALESCE(ROUND(seconds * 100 / NULLIF(EXTRACT(EPOCH FROM p.until - p.since)::NUMERIC, 0), 2), 0)::FLOAT8 AS utilization \
+         FROM rented \
+         CROSS JOIN period p \
+         ORDER BY name, id",
+    )
+    .bind::<Timestamp, _>(from)
+    .bind::<Timestamp, _>(to)
+    .load::<UtilizationRowDb>(conn)
+    .await
+    .map_err(CarSharingError::from)?;
+
+    Ok(res.into_iter().map(UtilizationReportRow::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use diesel::ExpressionMethods;
+    use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
+    use serial_test::serial;
+
+    use crate::config::config;
+    use crate::infra::db::schema::orders;
+    use crate::infra::services::cars_service::{self, NewCarDb};
+    use crate::infra::services::users_service::{self, NewUserDb};
+
+    use super::*;
+
+    async fn create_connection_pool() -> DbPool {
+        let config = config().await;
+
+        let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(config.db_url());
+        bb8::Pool::builder().build(manager).await.unwrap()
+    }
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2001, 1, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_01_reports() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 800000001,
+            ..Default::default()
+        };
+
+        let user_id = users_service::insert_or_update(&pool, new_user)
+            .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let car = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "Reported".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        // A paid day, an unpaid hour and a rental still in progress, all in January 2001
+        for (start, end, paid) in [
+            (at(1, 0), Some(at(2, 0)), true),
+            (at(3, 0), Some(at(3, 1)), false),
+            (at(31, 0), None, false),
+        ] {
+            let conn = &mut get_conn(&pool).await.unwrap();
+
+            diesel::insert_into(orders::table)
+                .values((
+                    orders::user_id.eq(user_id),
+                    orders::car_id.eq(car.id),
+                    orders::start_rent_time.eq(start),
+                    orders::end_rent_time.eq(end),
+                    orders::status.eq("finished"),
+                    orders::paid.eq(paid),
+                ))
+                .execute(conn)
+                .await
+            .expect("Failed to insert order");
+        }
+
+        let revenue = get_revenue(&pool, RevenueGrouping::Car, at(1, 0), at(31, 12))
+            .await
+            .unwrap();
+        let row = revenue.iter().find(|row| row.key == car.id.to_string()).unwrap();
+
+        assert_eq!(row.orders, 3);
+        assert_eq!(row.rented_hours, 25.0);
+        assert_eq!((row.revenue, row.paid_revenue, row.unpaid_revenue), (170, 150, 20));
+
+        let monthly = get_revenue(&pool, RevenueGrouping::Month, at(1, 0), at(31, 12))
+            .await
+            .unwrap();
+        assert_eq!(monthly.len(), 1);
+        assert_eq!(monthly[0].key, "2001-01-01");
+
+        // 25 of the 120 hours
+        let utilization = get_utilization(&pool, at(1, 0), at(6, 0)).await.unwrap();
+        let row = utilization.iter().find(|row| row.car_id == car.id).unwrap();
+        assert_eq!((row.orders, row.rented_hours, row.utilization), (2, 25.0, 20.83));
+
+        let utilization = get_utilization(&pool, at(31, 0), at(31, 12)).await.unwrap();
+        let row = utilization.iter().find(|row| row.car_id == car.id).unwrap();
+        assert_eq!((row.orders, row.utilization), (1, 100.0));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_02_utilization_until_now() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 800000001,
+            ..Default::default()
+        };
+
+        let user_id = users_service::insert_or_update(&pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        let car = cars_service::insert(
+            &pool,
+            NewCarDb {
+                name: "In use".to_string(),
+                hourly_rate: 20,
+                daily_rate: 150,
+                weekly_rate: 800,
+                photos: None,
+                license_plate: None,
+                status: None,
+            },
+        )
+        .await
+        .expect("Failed to insert car");
+
+        let now = chrono::Utc::now().naive_utc();
+
+        // A rental that started two hours ago and is still in progress
+        diesel::insert_into(orders::table)
+            .values((
+                orders::user_id.eq(user_id),
+                orders::car_id.eq(car.id),
+                orders::start_rent_time.eq(now - chrono::Duration::hours(2)),
+                orders::status.eq("processing"),
+            ))
+            .execute(&mut get_conn(&pool).await.unwrap())
+            .await
+            .expect("Failed to insert order");
+
+        // Only the two hours rented out of the four elapsed count, not the ones to come
+        let utilization = get_utilization(
+            &pool,
+            now - chrono::Duration::hours(4),
+            now + chrono::Duration::hours(4),
+        )
+        .await
+        .unwrap();
+        let row = utilization.iter().find(|row| row.car_id == car.id).unwrap();
+        assert_eq!((row.orders, row.rented_hours, row.utilization), (1, 2.0, 50.0));
+
+        // A period yet to start has no time elapsed
+        let utilization = get_utilization(
+            &pool,
+            now + chrono::Duration::hours(1),
+            now + chrono::Duration::hours(4),
+        )
+        .await
+        .unwrap();
+        let row = utilization.iter().find(|row| row.car_id == car.id).unwrap();
+        assert_eq!((row.rented_hours, row.utilization), (0.0, 0.0));
+    }
+}
@@ -1,18 +1,23 @@
+use chrono::{Duration, NaiveDateTime, Utc};
 use diesel::{
-    Associations, ExpressionMethods, Insertable, Queryable, QueryDsl, Selectable, SelectableHelper,
+    Associations, BoolExpressionMethods, ExpressionMethods, Insertable, Queryable, QueryDsl,
+    Selectable, SelectableHelper,
 };
 use diesel_async::RunQueryDsl;
 use serde::{Deserialize, Serialize};
-use tracing::log::debug;
+use tracing::debug;
 use uuid::Uuid;
 
+use crate::config::config;
 use crate::error::{CarSharingError, Result};
 use crate::handlers::{DbPool, get_conn};
+use crate::handlers::auth::UserData;
 use crate::infra::db::schema::{sessions as sessions_table, users};
 use crate::infra::db::schema::sessions::dsl::*;
-use crate::infra::Random;
 use crate::infra::services::users_service::UserDb;
+use crate::models::role::Role;
 use crate::models::session_token::SessionToken;
+use crate::models::user_status::UserStatus;
 
 #[derive(Debug, Serialize, Queryable, Selectable, Associations)]
 #[diesel(belongs_to(UserDb, foreign_key = user_id))]
@@ -21,6 +26,12 @@ use crate::models::session_token::SessionToken;
 pub struct SessionDb {
     pub session_token: Vec<u8>,
     pub user_id: Uuid,
+    pub id: Uuid,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub expires_at: NaiveDateTime,
+    pub user_agent: Option<String>,
+    pub ip: Option<String>,
 }
 
 #[derive(Deserialize, Insertable)]
@@ -28,21 +39,37 @@ pub struct SessionDb {
 pub struct NewSessionDb {
     session_token: Vec<u8>,
     user_id: Uuid,
+    expires_at: NaiveDateTime,
+    user_agent: Option<String>,
+    ip: Option<String>,
 }
 
-pub async fn new_session(pool: &DbPool, user_id_req: Uuid, random: Random) -> Result<SessionToken> {
+/// How often `last_seen_at` is refreshed, to avoid a write on every request.
+const LAST_SEEN_RESOLUTION: i64 = 60;
+
+pub async fn new_session(
+    pool: &DbPool,
+    user_id_req: Uuid,
+    user_agent_req: Option<String>,
+    ip_req: Option<String>,
+) -> Result<SessionToken> {
     debug!("->> {:<12} - new_session", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
+    let config = config().await;
+
     // Generate new token
-    let session_token_generated = SessionToken::generate_new(random);
+    let session_token_generated = SessionToken::generate_new();
 
     // Create NewSession to insert into db
     let new_session = NewSessionDb {
         session_token: session_token_generated.into_database_value(),
         user_id: user_id_req,
+        expires_at: Utc::now().naive_utc() + Duration::seconds(config.session_lifetime()),
+        user_agent: user_agent_req,
+        ip: ip_req,
     };
 
     diesel::insert_into(sessions)
@@ -50,61 +77,192 @@ pub async fn new_session(pool: &DbPool, user_id_req: Uuid, random: Random) -> Re
         .returning(SessionDb::as_returning())
         .get_result(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
 
     Ok(session_token_generated)
 }
 
-pub async fn get_ids_by_token(pool: &DbPool, token: String) -> Result<(i32, Uuid)> {
-    debug!("->> {:<12} - get_telegram_id_by_token", "INFRASTRUCTURE");
+pub async fn get_ids_by_token(pool: &DbPool, token: String) -> Result<UserData> {
+    debug!("->> {:<12} - get_ids_by_token", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
-    // Convert String to Vec<u8>
-    let session_token_bytes = token.parse::<u128>()?.to_le_bytes().to_vec();
+    let config = config().await;
+    let now = Utc::now().naive_utc();
+
+    // Sessions are looked up by the digest of the token
+    let session_token_bytes = SessionToken::from_cookie_value(&token)?.into_database_value();
 
-    let user_db = sessions
+    // Only sessions within both the absolute and the idle timeout are valid
+    let (session_db, user_db) = sessions
         .filter(session_token.eq(session_token_bytes))
+        .filter(expires_at.gt(now))
+        .filter(last_seen_at.gt(now - Duration::seconds(config.session_idle_timeout())))
         .inner_join(users::table)
-        .select(UserDb::as_select())
-        .first::<UserDb>(conn)
+        .select((SessionDb::as_select(), UserDb::as_select()))
+        .first::<(SessionDb, UserDb)>(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
+
+    if session_db.last_seen_at < now - Duration::seconds(LAST_SEEN_RESOLUTION) {
+        diesel::update(sessions.filter(id.eq(session_db.id)))
+            .set(last_seen_at.eq(now))
+            .execute(conn)
+            .await
+            .map_err(CarSharingError::from)?;
+    }
 
-    Ok((user_db.telegram_id, user_db.id))
+    Ok(session_user_data(&session_db, &user_db, now))
 }
 
-pub async fn delete_session(pool: &DbPool, token: String) -> Result<()> {
-    use crate::infra::db::schema::sessions::dsl::*;
+/// Checks a session again by its id, without counting it as activity, e.g. for streams
+/// opened with it.
+pub async fn get_user_data(pool: &DbPool, session_id_req: Uuid) -> Result<UserData> {
+    debug!("->> {:<12} - get_user_data", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let config = config().await;
+    let now = Utc::now().naive_utc();
 
+    let (session_db, user_db) = sessions
+        .filter(id.eq(session_id_req))
+        .filter(expires_at.gt(now))
+        .filter(last_seen_at.gt(now - Duration::seconds(config.session_idle_timeout())))
+        .inner_join(users::table)
+        .select((SessionDb::as_select(), UserDb::as_select()))
+        .first::<(SessionDb, UserDb)>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(session_user_data(&session_db, &user_db, now))
+}
+
+fn session_user_data(session_db: &SessionDb, user_db: &UserDb, now: NaiveDateTime) -> UserData {
+    UserData {
+        telegram_id: user_db.telegram_id,
+        user_id: user_db.id,
+        session_id: Option::from(session_db.id),
+        api_token_id: None,
+        scopes: None,
+        role: Role::from(user_db.role.as_str()),
+        status: UserStatus::effective(&user_db.status, user_db.status_until, now),
+    }
+}
+
+pub async fn get_user_sessions(pool: &DbPool, user_id_req: Uuid) -> Result<Vec<SessionDb>> {
+    debug!("->> {:<12} - get_user_sessions", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let config = config().await;
+    let now = Utc::now().naive_utc();
+
+    let res = sessions
+        .filter(user_id.eq(user_id_req))
+        .filter(expires_at.gt(now))
+        .filter(last_seen_at.gt(now - Duration::seconds(config.session_idle_timeout())))
+        .order(last_seen_at.desc())
+        .select(SessionDb::as_select())
+        .load::<SessionDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(res)
+}
+
+pub async fn delete_session(pool: &DbPool, token: String) -> Result<()> {
     debug!("->> {:<12} - delete_session", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
-    // Convert String to Vec<u8>
-    let session_token_bytes = token.parse::<u128>()?.to_le_bytes().to_vec();
+    // Cookies from before tokens were hashed, or garbage, can't match any session
+    let Ok(token) = SessionToken::from_cookie_value(&token) else {
+        return Ok(());
+    };
+
+    // Sessions are looked up by the digest of the token
+    let session_token_bytes = token.into_database_value();
 
     diesel::delete(sessions.filter(session_token.eq(session_token_bytes)))
         .execute(conn)
         .await
-        .map_err(|err| CarSharingError::from(err))?;
+        .map_err(CarSharingError::from)?;
+
+    Ok(())
+}
+
+pub async fn delete_user_sessions(pool: &DbPool, user_id_req: Uuid) -> Result<()> {
+    debug!("->> {:<12} - delete_user_sessions", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    diesel::delete(sessions.filter(user_id.eq(user_id_req)))
+        .execute(conn)
+        .await
+        .map_err(CarSharingError::from)?;
 
     Ok(())
 }
 
+pub async fn delete_user_session(pool: &DbPool, user_id_req: Uuid, session_id: Uuid) -> Result<()> {
+    debug!("->> {:<12} - delete_user_session", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let deleted = diesel::delete(
+        sessions
+            .filter(id.eq(session_id))
+            .filter(user_id.eq(user_id_req)),
+    )
+    .execute(conn)
+    .await
+    .map_err(CarSharingError::from)?;
+
+    if deleted == 0 {
+        return Err(CarSharingError::DatabaseNotFound);
+    }
+
+    Ok(())
+}
+
+/// Purges sessions past their absolute or idle timeout.
+pub async fn delete_expired(pool: &DbPool) -> Result<usize> {
+    debug!("->> {:<12} - delete_expired", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let config = config().await;
+    let now = Utc::now().naive_utc();
+
+    let deleted = diesel::delete(
+        sessions.filter(
+            expires_at
+                .le(now)
+                .or(last_seen_at.le(now - Duration::seconds(config.session_idle_timeout()))),
+        ),
+    )
+    .execute(conn)
+    .await
+    .map_err(CarSharingError::from)?;
+
+    Ok(deleted)
+}
+
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
-
     use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
-    use rand_chacha::ChaCha8Rng;
-    use rand_core::{OsRng, RngCore, SeedableRng};
     use serial_test::serial;
 
     use crate::config::config;
-    use crate::infra::services::users_service::insert_if_not_exists;
+    use crate::infra::services::users_service::{insert_or_update, NewUserDb};
 
     use super::*;
 
@@ -115,13 +273,34 @@ mod tests {
         bb8::Pool::builder().build(manager).await.unwrap()
     }
 
-    async fn get_first_session(pool: &DbPool) -> SessionDb {
+    async fn create_session(pool: &DbPool) -> SessionToken {
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        new_session(
+            pool,
+            user_id_res,
+            Option::from("hurl/4.3.0".to_string()),
+            Option::from("127.0.0.1".to_string()),
+        )
+        .await
+        .expect("Failed to create a session")
+    }
+
+    async fn get_session(pool: &DbPool, token: SessionToken) -> SessionDb {
         let conn = &mut get_conn(pool).await.unwrap();
 
         sessions
+            .filter(session_token.eq(token.into_database_value()))
             .first::<SessionDb>(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .expect("Can't find a session")
     }
 
@@ -135,7 +314,7 @@ mod tests {
         diesel::delete(sessions.filter(session_token.is_not_null()))
             .execute(conn)
             .await
-            .map_err(|err| CarSharingError::from(err))
+            .map_err(CarSharingError::from)
             .unwrap();
     }
 
@@ -144,17 +323,11 @@ mod tests {
     async fn test_02_new_session() {
         let pool = create_connection_pool().await;
 
-        let random = ChaCha8Rng::seed_from_u64(OsRng.next_u64());
-
-        let user_id_res = insert_if_not_exists(&pool, 443621429)
-            .await
-            .expect("Failed to insert user or retrieve existing ID");
+        let token = create_session(&pool).await;
 
-        assert!(
-            !new_session(&pool, user_id_res, Arc::new(Mutex::new(random)),)
-                .await
-                .is_err()
-        );
+        // Only the digest of the token is stored
+        let session_db = get_session(&pool, token).await;
+        assert_eq!(session_db.session_token, token.into_database_value());
     }
 
     #[tokio::test]
@@ -162,30 +335,66 @@ mod tests {
     async fn test_03_get_ids_by_token() {
         let pool = create_connection_pool().await;
 
-        let session_token_res = get_first_session(&pool).await;
+        let token = create_session(&pool).await;
 
-        let mut arr = [0u8; 16];
+        let res = get_ids_by_token(&pool, token.into_cookie_value())
+            .await
+            .expect("Failed to get user by token");
 
-        arr.copy_from_slice(&session_token_res.session_token);
+        assert_eq!(res.session_id, Option::from(get_session(&pool, token).await.id));
+        assert!(get_ids_by_token(&pool, "invalid".to_string()).await.is_err());
+    }
 
-        let session_token_string = u128::from_le_bytes(arr).to_string();
+    #[tokio::test]
+    #[serial]
+    async fn test_04_get_user_sessions() {
+        let pool = create_connection_pool().await;
+
+        let session_db = get_session(&pool, create_session(&pool).await).await;
 
-        assert!(!get_ids_by_token(&pool, session_token_string).await.is_err());
+        let res = get_user_sessions(&pool, session_db.user_id)
+            .await
+            .expect("Failed to get user sessions");
+
+        assert!(res.iter().any(|session| session.id == session_db.id));
     }
 
     #[tokio::test]
     #[serial]
-    async fn test_04_delete_session() {
+    async fn test_05_expired_session() {
         let pool = create_connection_pool().await;
 
-        let session_token_res = get_first_session(&pool).await;
+        let token = create_session(&pool).await;
+
+        let conn = &mut get_conn(&pool).await.unwrap();
+
+        diesel::update(sessions.filter(session_token.eq(token.into_database_value())))
+            .set(expires_at.eq(Utc::now().naive_utc()))
+            .execute(conn)
+            .await
+            .unwrap();
+
+        assert!(get_ids_by_token(&pool, token.into_cookie_value()).await.is_err());
+        assert!(get_user_data(&pool, get_session(&pool, token).await.id).await.is_err());
+        assert!(delete_expired(&pool).await.expect("Failed to purge sessions") >= 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_06_delete_session() {
+        let pool = create_connection_pool().await;
 
-        let mut arr = [0u8; 16];
+        let token = create_session(&pool).await;
 
-        arr.copy_from_slice(&session_token_res.session_token);
+        let session_db = get_session(&pool, token).await;
+        assert!(get_user_data(&pool, session_db.id).await.is_ok());
 
-        let session_token_string = u128::from_le_bytes(arr).to_string();
+        assert!(delete_session(&pool, token.into_cookie_value()).await.is_ok());
+        assert!(get_ids_by_token(&pool, token.into_cookie_value()).await.is_err());
+        assert!(get_user_data(&pool, session_db.id).await.is_err());
 
-        assert!(!delete_session(&pool, session_token_string).await.is_err());
+        // Decimal tokens of the cookies set before tokens were hashed
+        let old_token = u128::MAX.to_string();
+        assert!(delete_session(&pool, old_token).await.is_ok());
     }
 }
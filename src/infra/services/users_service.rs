@@ -1,15 +1,24 @@
-use chrono::NaiveDateTime;
-use diesel::{ExpressionMethods, Insertable, OptionalExtension, Queryable, QueryDsl, Selectable};
-use diesel_async::RunQueryDsl;
+use chrono::{NaiveDateTime, Utc};
+use diesel::{
+    AsChangeset, BoolExpressionMethods, ExpressionMethods, Insertable, Queryable, QueryDsl,
+    Selectable, SelectableHelper,
+};
+use diesel::upsert::excluded;
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
+use diesel_async::scoped_futures::ScopedFutureExt;
 use serde::{Deserialize, Serialize};
-use tracing::log::debug;
+use tracing::debug;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
-use crate::config::config;
 use crate::error::{CarSharingError, Result};
 use crate::handlers::{DbPool, get_conn};
+use crate::handlers::users::{UpdateProfileRequest, UserResponse, UserStatusEventResponse};
+use crate::infra::db::schema::{sessions, user_status_events};
 use crate::infra::db::schema::users as users_table;
 use crate::infra::db::schema::users::dsl::*;
+use crate::models::role::Role;
+use crate::models::user_status::UserStatus;
 
 #[derive(Serialize, Queryable, Selectable)]
 #[diesel(table_name = users_table)]
@@ -19,79 +28,350 @@ pub struct UserDb {
     pub role: String,
     pub created_at: NaiveDateTime,
     pub status: String,
-    pub telegram_id: i32,
+    pub telegram_id: i64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub photo_url: Option<String>,
+    pub phone_number: Option<String>,
+    pub email: Option<String>,
+    pub updated_at: Option<NaiveDateTime>,
+    pub status_reason: Option<String>,
+    pub status_until: Option<NaiveDateTime>,
 }
 
-#[derive(Deserialize, Insertable)]
+#[derive(Serialize, Queryable, Selectable)]
+#[diesel(table_name = user_status_events)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct UserStatusEventDb {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub admin_id: Option<Uuid>,
+    pub status: String,
+    pub reason: Option<String>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = user_status_events)]
+struct NewUserStatusEventDb {
+    user_id: Uuid,
+    admin_id: Option<Uuid>,
+    status: String,
+    reason: Option<String>,
+    expires_at: Option<NaiveDateTime>,
+}
+
+#[derive(Default, Deserialize, Insertable)]
 #[diesel(table_name = users_table)]
 pub struct NewUserDb {
-    pub telegram_id: i32,
+    pub telegram_id: i64,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub username: Option<String>,
+    pub photo_url: Option<String>,
 }
 
-pub async fn insert_if_not_exists(pool: &DbPool, telegram_id_req: i32) -> Result<Uuid> {
-    debug!("->> {:<12} - insert_if_not_exists", "INFRASTRUCTURE");
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct UsersFilter {
+    pub role: Option<Role>,
+}
+
+#[derive(AsChangeset)]
+#[diesel(table_name = users_table)]
+struct UpdateProfileChangeset {
+    phone_number: Option<Option<String>>,
+    email: Option<Option<String>>,
+}
+
+pub async fn insert_or_update(pool: &DbPool, new_user: NewUserDb) -> Result<Uuid> {
+    debug!("->> {:<12} - insert_or_update", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
-    // Get existing user if exists
-    let existing_user = users
-        .filter(telegram_id.eq(telegram_id_req))
-        .first::<UserDb>(conn)
+    // Insert or refresh the Telegram profile in one statement, relying on the unique
+    // index on telegram_id, so concurrent first logins can't create duplicate users
+    let user_id = diesel::insert_into(users)
+        .values(&new_user)
+        .on_conflict(telegram_id)
+        .do_update()
+        .set((
+            first_name.eq(excluded(first_name)),
+            last_name.eq(excluded(last_name)),
+            username.eq(excluded(username)),
+            photo_url.eq(excluded(photo_url)),
+        ))
+        .returning(id)
+        .get_result(conn)
         .await
-        .optional()
-        .map_err(|err| CarSharingError::from(err))?;
-
-    // Create new user if necessary
-    let user_id = match existing_user {
-        Some(user) => user.id, // User already exists, use their ID
-        None => {
-            let new_user = NewUserDb {
-                telegram_id: telegram_id_req,
-            }; // Create a new user struct
-            diesel::insert_into(users)
-                .values(&new_user)
-                .returning(id)
-                .get_result(conn)
-                .await
-                .map_err(|err| CarSharingError::from(err))?
-        }
-    };
+        .map_err(CarSharingError::from)?;
 
     Ok(user_id)
 }
 
-pub async fn check_if_admin(pool: &DbPool, user_id_req: Uuid) -> Result<bool> {
-    debug!("->> {:<12} - insert_if_not_exists", "INFRASTRUCTURE");
+pub async fn get(pool: &DbPool, user_id_req: Uuid) -> Result<UserResponse> {
+    debug!("->> {:<12} - get", "INFRASTRUCTURE");
 
     // Get a database connection from the pool and handle any potential errors
     let conn = &mut get_conn(pool).await?;
 
-    let config = config().await;
+    let res = users
+        .filter(id.eq(user_id_req))
+        .select(UserDb::as_select())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(UserResponse::from(res))
+}
 
-    let admin_ids: Vec<i32> = config
-        .admin_ids()
-        .split(',')
-        .map(|s| s.trim().parse().expect("Invalid integer"))
-        .collect();
+pub async fn update_profile(
+    pool: &DbPool,
+    user_id_req: Uuid,
+    updated_profile: UpdateProfileRequest,
+) -> Result<UserResponse> {
+    debug!("->> {:<12} - update_profile", "INFRASTRUCTURE");
 
-    let user_db = users
-        .filter(id.eq(user_id_req))
-        .first::<UserDb>(conn)
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let changeset = UpdateProfileChangeset {
+        phone_number: updated_profile.phone_number,
+        email: updated_profile.email,
+    };
+
+    let res = diesel::update(users.find(user_id_req))
+        .set(&changeset)
+        .returning(UserDb::as_returning())
+        .get_result(conn)
         .await
-        .optional()
-        .map_err(|err| CarSharingError::from(err))?;
-
-    match user_db {
-        Some(user_db) => {
-            if admin_ids.contains(&user_db.telegram_id) {
-                Ok(true)
-            } else {
-                Ok(false)
+        .map_err(CarSharingError::from)?;
+
+    Ok(UserResponse::from(res))
+}
+
+pub async fn get_all(pool: &DbPool, filter: UsersFilter) -> Result<Vec<UserResponse>> {
+    debug!("->> {:<12} - get_all", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let mut query = users.into_boxed::<diesel::pg::Pg>();
+
+    if let Some(role_from_filter) = filter.role {
+        query = query.filter(role.eq(role_from_filter.as_str()));
+    }
+
+    let res = query
+        .select(UserDb::as_select())
+        .order(created_at.asc())
+        .load::<UserDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let list_response = res.into_iter().map(UserResponse::from).collect();
+
+    Ok(list_response)
+}
+
+/// Whether an active admin remains once `user_id_req` loses the role, among the `admins`.
+fn keeps_an_admin(admins: &[Uuid], user_id_req: Uuid) -> bool {
+    admins.iter().any(|admin_id| *admin_id != user_id_req)
+}
+
+/// Fails with `LastAdmin` when `user_id_req` is the only active admin left. The admins
+/// are locked until the end of the transaction, so two admins can't be removed at once.
+async fn check_other_admin(conn: &mut AsyncPgConnection, user_id_req: Uuid) -> Result<()> {
+    let admins = users
+        .filter(role.eq(Role::Admin.as_str()))
+        // Blocks past their end date no longer apply
+        .filter(
+            status
+                .ne(UserStatus::Blocked.as_str())
+                .or(status_until.lt(Utc::now().naive_utc())),
+        )
+        .select(id)
+        .for_update()
+        .load::<Uuid>(conn)
+        .await?;
+
+    if admins.contains(&user_id_req) && !keeps_an_admin(&admins, user_id_req) {
+        return Err(CarSharingError::LastAdmin);
+    }
+
+    Ok(())
+}
+
+pub async fn update_role(pool: &DbPool, user_id_req: Uuid, new_role: Role) -> Result<UserResponse> {
+    debug!("->> {:<12} - update_role", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = conn
+        .transaction::<UserDb, CarSharingError, _>(|conn| {
+            async move {
+                if new_role != Role::Admin {
+                    check_other_admin(conn, user_id_req).await?;
+                }
+
+                let user_db = diesel::update(users.find(user_id_req))
+                    .set(role.eq(new_role.as_str()))
+                    .returning(UserDb::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                Ok(user_db)
             }
-        }
-        None => Err(CarSharingError::DatabaseNotFound),
+            .scope_boxed()
+        })
+        .await?;
+
+    Ok(UserResponse::from(res))
+}
+
+/// Changes the status of a user and records it in the status history. Blocking a user
+/// also revokes all of their sessions, unless they're the last active admin.
+pub async fn set_status(
+    pool: &DbPool,
+    user_id_req: Uuid,
+    admin_id_req: Uuid,
+    new_status: UserStatus,
+    reason: Option<String>,
+    expires_at: Option<NaiveDateTime>,
+) -> Result<UserResponse> {
+    debug!("->> {:<12} - set_status", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = conn
+        .transaction::<UserDb, CarSharingError, _>(|conn| {
+            async move {
+                if new_status == UserStatus::Blocked {
+                    check_other_admin(conn, user_id_req).await?;
+                }
+
+                let user_db = diesel::update(users.find(user_id_req))
+                    .set((
+                        status.eq(new_status.as_str()),
+                        status_reason.eq(&reason),
+                        status_until.eq(expires_at),
+                    ))
+                    .returning(UserDb::as_returning())
+                    .get_result(conn)
+                    .await?;
+
+                let new_event = NewUserStatusEventDb {
+                    user_id: user_id_req,
+                    admin_id: Option::from(admin_id_req),
+                    status: new_status.as_str().to_string(),
+                    reason,
+                    expires_at,
+                };
+
+                diesel::insert_into(user_status_events::table)
+                    .values(&new_event)
+                    .execute(conn)
+                    .await?;
+
+                if new_status == UserStatus::Blocked {
+                    diesel::delete(sessions::table.filter(sessions::user_id.eq(user_id_req)))
+                        .execute(conn)
+                        .await?;
+                }
+
+                Ok(user_db)
+            }
+            .scope_boxed()
+        })
+        .await?;
+
+    Ok(UserResponse::from(res))
+}
+
+pub async fn get_status_events(
+    pool: &DbPool,
+    user_id_req: Uuid,
+) -> Result<Vec<UserStatusEventResponse>> {
+    debug!("->> {:<12} - get_status_events", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = user_status_events::table
+        .filter(user_status_events::user_id.eq(user_id_req))
+        .order(user_status_events::created_at.desc())
+        .select(UserStatusEventDb::as_select())
+        .load::<UserStatusEventDb>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    let list_response = res
+        .into_iter()
+        .map(UserStatusEventResponse::from)
+        .collect();
+
+    Ok(list_response)
+}
+
+/// Promotes the given Telegram ids to admins, creating their users if necessary,
+/// but only while there is no admin in the database yet.
+pub async fn bootstrap_admins(pool: &DbPool, admin_telegram_ids: &[i64]) -> Result<()> {
+    debug!("->> {:<12} - bootstrap_admins", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let admins_count = users
+        .filter(role.eq(Role::Admin.as_str()))
+        .count()
+        .get_result::<i64>(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    if admins_count > 0 || admin_telegram_ids.is_empty() {
+        return Ok(());
     }
+
+    let new_admins = admin_telegram_ids
+        .iter()
+        .map(|admin_telegram_id| (telegram_id.eq(admin_telegram_id), role.eq(Role::Admin.as_str())))
+        .collect::<Vec<_>>();
+
+    diesel::insert_into(users)
+        .values(&new_admins)
+        .on_conflict(telegram_id)
+        .do_update()
+        .set(role.eq(excluded(role)))
+        .execute(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(())
+}
+
+/// Creates the user with the given Telegram id as an admin, or promotes the existing one.
+pub async fn create_admin(pool: &DbPool, admin_telegram_id: i64) -> Result<UserResponse> {
+    debug!("->> {:<12} - create_admin", "INFRASTRUCTURE");
+
+    // Get a database connection from the pool and handle any potential errors
+    let conn = &mut get_conn(pool).await?;
+
+    let res = diesel::insert_into(users)
+        .values((telegram_id.eq(admin_telegram_id), role.eq(Role::Admin.as_str())))
+        .on_conflict(telegram_id)
+        .do_update()
+        .set(role.eq(excluded(role)))
+        .returning(UserDb::as_returning())
+        .get_result(conn)
+        .await
+        .map_err(CarSharingError::from)?;
+
+    Ok(UserResponse::from(res))
 }
 
 #[cfg(test)]
@@ -117,15 +397,182 @@ mod tests {
         let conn = &mut get_conn(&pool).await.unwrap();
 
         let res = sql_query("SELECT 1").execute(conn).await;
-        assert!(!res.is_err());
+        assert!(res.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_insert_or_update() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            ..Default::default()
+        };
+
+        insert_or_update(&pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
     }
 
     #[tokio::test]
-    async fn test_insert_if_not_exists() {
+    async fn test_insert_or_update_concurrently() {
         let pool = create_connection_pool().await;
 
-        insert_if_not_exists(&pool, 443621429)
+        // Telegram ids don't fit into 32 bits anymore
+        let new_user = || NewUserDb {
+            telegram_id: 7_000_000_001,
+            ..Default::default()
+        };
+
+        let (first, second) = tokio::join!(
+            insert_or_update(&pool, new_user()),
+            insert_or_update(&pool, new_user())
+        );
+
+        assert_eq!(
+            first.expect("Failed to insert user"),
+            second.expect("Failed to insert user")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_profile() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 443621429,
+            first_name: Option::from("Maxud".to_string()),
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(&pool, new_user)
             .await
             .expect("Failed to insert user or retrieve existing ID");
+
+        let update_profile_req = UpdateProfileRequest {
+            phone_number: Some(Some("+10000000000".to_string())),
+            email: Some(Some("maxud@example.com".to_string())),
+        };
+
+        let res = update_profile(&pool, user_id_res, update_profile_req)
+            .await
+            .expect("Failed to update a profile");
+
+        assert_eq!(res.first_name, Option::from("Maxud".to_string()));
+        assert_eq!(res.phone_number, Option::from("+10000000000".to_string()));
+
+        // A `null` email clears it, the missing phone number is kept
+        let update_profile_req = serde_json::from_str::<UpdateProfileRequest>(r#"{"email": null}"#)
+            .expect("Failed to parse the request");
+
+        let res = update_profile(&pool, user_id_res, update_profile_req)
+            .await
+            .expect("Failed to update a profile");
+
+        assert_eq!(res.phone_number, Option::from("+10000000000".to_string()));
+        assert_eq!(res.email, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_role() {
+        let pool = create_connection_pool().await;
+
+        let new_user = NewUserDb {
+            telegram_id: 7_000_000_002,
+            ..Default::default()
+        };
+
+        let user_id_res = insert_or_update(&pool, new_user)
+            .await
+            .expect("Failed to insert user or retrieve existing ID");
+
+        let granted = update_role(&pool, user_id_res, Role::Admin)
+            .await
+            .expect("Failed to grant a role");
+
+        assert_eq!(granted.role, Role::Admin.as_str());
+
+        let revoked = update_role(&pool, user_id_res, Role::User)
+            .await
+            .expect("Failed to revoke a role");
+
+        assert_eq!(revoked.role, Role::User.as_str());
+    }
+
+    #[test]
+    fn test_keeps_an_admin() {
+        let (admin_id, other_admin_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(keeps_an_admin(&[admin_id, other_admin_id], admin_id));
+        assert!(!keeps_an_admin(&[admin_id], admin_id));
+        assert!(!keeps_an_admin(&[], admin_id));
+    }
+
+    #[tokio::test]
+    async fn test_create_admin() {
+        let pool = create_connection_pool().await;
+
+        let created = create_admin(&pool, 7_000_000_003)
+            .await
+            .expect("Failed to create an admin");
+
+        assert_eq!(created.role, Role::Admin.as_str());
+
+        let promoted = create_admin(&pool, 7_000_000_003)
+            .await
+            .expect("Failed to promote an admin");
+
+        assert_eq!(created.id, promoted.id);
+    }
+
+    #[tokio::test]
+    async fn test_set_status() {
+        let pool = create_connection_pool().await;
+
+        let admin_id_res = insert_or_update(
+            &pool,
+            NewUserDb {
+                telegram_id: 443621429,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let user_id_res = insert_or_update(
+            &pool,
+            NewUserDb {
+                telegram_id: 7_000_000_004,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to insert user or retrieve existing ID");
+
+        let blocked = set_status(
+            &pool,
+            user_id_res,
+            admin_id_res,
+            UserStatus::Blocked,
+            Option::from("Abusive behaviour".to_string()),
+            None,
+        )
+        .await
+        .expect("Failed to block a user");
+
+        assert_eq!(blocked.status, UserStatus::Blocked);
+
+        let unblocked = set_status(&pool, user_id_res, admin_id_res, UserStatus::Active, None, None)
+            .await
+            .expect("Failed to unblock a user");
+
+        assert_eq!(unblocked.status, UserStatus::Active);
+
+        let events = get_status_events(&pool, user_id_res)
+            .await
+            .expect("Failed to get status events");
+
+        assert!(events.len() >= 2);
+        assert_eq!(events[0].status, UserStatus::Active);
     }
 }
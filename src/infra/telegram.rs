@@ -0,0 +1,36 @@
+use reqwest::StatusCode;
+use serde_json::json;
+use tracing::debug;
+
+use crate::config::config;
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// Sends a message to a user through the login bot. Users who never started
+/// a chat with the bot can't receive messages, Telegram rejects those with a
+/// client error status.
+///
+/// Errors leave out the request URL, as it contains the bot token.
+pub async fn send_message(chat_id: i64, text: &str) -> Result<(), reqwest::Error> {
+    debug!("->> {:<12} - send_message", "INFRASTRUCTURE");
+
+    let config = config().await;
+
+    reqwest::Client::new()
+        .post(format!("{}/bot{}/sendMessage", TELEGRAM_API_URL, config.bot_token()))
+        .json(&json!({ "chat_id": chat_id, "text": text }))
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(reqwest::Error::without_url)?;
+
+    Ok(())
+}
+
+/// Whether Telegram refused the message for good because the user can't be reached:
+/// 400 when they never started a chat with the bot, 403 when they blocked it. Other
+/// failures, a wrong bot token included, may succeed once fixed or on a later try.
+pub fn is_undeliverable(err: &reqwest::Error) -> bool {
+    err.status()
+        .is_some_and(|status| matches!(status, StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN))
+}
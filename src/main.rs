@@ -1,34 +1,203 @@
-use axum::Router;
-use tracing::log::debug;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::time::Duration;
 
-use crate::config::config;
-use crate::routes::app_router;
+use tokio::net::TcpListener;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+use tracing_subscriber::EnvFilter;
 
+use crate::cli::{Command, USAGE};
+use crate::config::{Config, LogFormat, try_config};
+use crate::error::ServerError;
+use crate::infra::db::{create_pool, run_migrations};
+use crate::infra::jobs::{
+    spawn_license_expiry_notifications, spawn_order_events_listener, spawn_sessions_cleanup,
+};
+use crate::infra::metrics::install_recorder;
+use crate::infra::order_events::OrderEvents;
+use crate::infra::services::users_service;
+use crate::routes::{app_router, metrics_router};
+
+mod cli;
 mod config;
 mod error;
 mod handlers;
 mod infra;
 mod middlewares;
 mod models;
+mod openapi;
 mod routes;
 
 #[tokio::main]
-async fn main() {
-    let config = config().await;
+async fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+
+    let command = match Command::parse(&args) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Ok(command) => command,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            // EX_USAGE from sysexits.h
+            return ExitCode::from(64);
+        }
+    };
+
+    let config = match try_config().await {
+        Ok(config) => config,
+        Err(err) => {
+            eprint!("{}", err);
+            // EX_CONFIG from sysexits.h
+            return ExitCode::from(78);
+        }
+    };
+
+    // RUST_LOG filters the output, e.g. RUST_LOG=car_sharing=debug
+    let subscriber = tracing_subscriber::fmt().with_env_filter(EnvFilter::new(config.log_level()));
+
+    match config.log_format() {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+
+    let res = match command {
+        Command::Serve => serve(config).await,
+        command => cli::run(command, config).await,
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            error!("{}", err);
+            ExitCode::from(err.exit_code())
+        }
+    }
+}
+
+/// Runs the server until SIGINT or SIGTERM, then stops accepting connections and gives
+/// in-flight requests and background jobs the shutdown timeout to finish.
+async fn serve(config: &Config) -> Result<(), ServerError> {
+    install_recorder();
+
+    run_migrations(
+        config.db_url(),
+        config.db_connect_retries(),
+        config.db_connect_retry_delay(),
+    )
+    .await?;
+
+    let pool = create_pool(config).await?;
+
+    users_service::bootstrap_admins(&pool, config.admin_ids())
+        .await
+        .map_err(ServerError::BootstrapAdmins)?;
+
+    let shutdown = CancellationToken::new();
+
+    listen_for_signals(shutdown.clone())?;
+
+    let order_events = OrderEvents::new(shutdown.clone());
 
-    env_logger::init();
+    let mut jobs = vec![
+        spawn_sessions_cleanup(pool.clone(), config.session_cleanup_interval(), shutdown.clone()),
+        spawn_order_events_listener(
+            config.db_url().to_string(),
+            order_events.clone(),
+            config.db_connect_retry_delay(),
+            shutdown.clone(),
+        ),
+    ];
 
-    let app = Router::new().nest("/api", app_router(config.db_url()).await);
+    if config.license_expiry_notifications_enabled() {
+        jobs.push(spawn_license_expiry_notifications(
+            pool.clone(),
+            config.license_check_interval(),
+            config.license_expiry_notice(),
+            shutdown.clone(),
+        ));
+    }
 
     let host = config.server_host();
+
+    if let Some(metrics_port) = config.metrics_port().filter(|_| config.metrics_enabled()) {
+        let metrics_listener = bind(format!("{}:{}", host, metrics_port)).await?;
+
+        debug!("METRICS LISTENING on {:?}\n", metrics_listener.local_addr());
+        tokio::spawn(
+            axum::serve(metrics_listener, metrics_router(pool.clone()))
+                .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+                .into_future(),
+        );
+    }
+
+    let app = app_router(config, pool, order_events);
+
     let port = config.server_port();
 
-    let address = format!("{}:{}", host, port);
+    let listener = bind(format!("{}:{}", host, port)).await?;
+
+    debug!("LISTENING on {:?}\n", listener.local_addr());
+    let mut server = tokio::spawn(
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+        .into_future(),
+    );
+
+    tokio::select! {
+        res = &mut server => {
+            shutdown.cancel();
+            return res
+                .map_err(|err| ServerError::Serve(err.into()))?
+                .map_err(ServerError::Serve);
+        }
+        _ = shutdown.cancelled() => {}
+    }
+
+    info!("Shutting down, waiting for in-flight requests and jobs");
+
+    let drained = tokio::time::timeout(Duration::from_secs(config.shutdown_timeout()), async {
+        let _ = server.await;
 
-    let listener = tokio::net::TcpListener::bind(address).await.unwrap();
+        for job in jobs {
+            let _ = job.await;
+        }
+    })
+    .await;
 
-    debug!("LISTENING on {:?}\n", listener.local_addr().unwrap());
-    axum::serve(listener, app.into_make_service())
+    if drained.is_err() {
+        warn!("Shutdown timeout elapsed, dropping the remaining requests and jobs");
+    }
+
+    Ok(())
+}
+
+async fn bind(address: String) -> Result<TcpListener, ServerError> {
+    TcpListener::bind(&address)
         .await
-        .unwrap();
+        .map_err(|err| ServerError::Bind(address, err))
+}
+
+/// Cancels `shutdown` on the first SIGINT or SIGTERM.
+fn listen_for_signals(shutdown: CancellationToken) -> Result<(), ServerError> {
+    let mut interrupt = signal(SignalKind::interrupt()).map_err(ServerError::Signal)?;
+    let mut terminate = signal(SignalKind::terminate()).map_err(ServerError::Signal)?;
+
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = interrupt.recv() => info!("Received SIGINT"),
+            _ = terminate.recv() => info!("Received SIGTERM"),
+        }
+
+        shutdown.cancel();
+    });
+
+    Ok(())
 }
@@ -1,12 +1,107 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
 use axum::{body::Body, extract::State, http::Request, middleware::Next, response::IntoResponse};
-use axum::response::Redirect;
+use axum::http::header::{AUTHORIZATION, HOST, LINK, ORIGIN, REFERER};
+use axum::extract::{ConnectInfo, MatchedPath};
+use axum::http::HeaderValue;
+use metrics::{counter, histogram};
 use tower_cookies::Cookies;
-use tracing::log::debug;
+use tracing::{debug, info, info_span, Instrument, Span};
+use tracing::field::{display, Empty};
+use uuid::Uuid;
 
-use crate::handlers::auth::{SESSION_TOKEN, UserData};
+use crate::config::config;
+use crate::handlers::auth::{removal_session_cookie, SESSION_TOKEN, UserData};
 use crate::handlers::DbPool;
-use crate::infra::services::{sessions_service, users_service};
+use crate::infra::metrics::{HTTP_REQUEST_DURATION_SECONDS, HTTP_REQUESTS_TOTAL};
+use crate::infra::services::{api_tokens_service, sessions_service};
 use crate::models::HandlerError;
+use crate::models::permission::Permission;
+use crate::models::user_status::UserStatus;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+const X_REQUEST_ID: &str = "x-request-id";
+
+/// Id of the request being handled, if called while handling one.
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(String::clone).ok()
+}
+
+/// Runs the request in a span carrying its id, route and user, logging its status and
+/// latency once done and recording them in the HTTP metrics. The id is taken from
+/// `X-Request-Id` when the client sent a usable one, generated otherwise, and echoed
+/// back in the response.
+pub async fn trace_request(request: Request<Body>, next: Next) -> impl IntoResponse {
+    let request_id = request
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|request_id| is_valid_request_id(request_id))
+        .map(String::from)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let method = request.method().to_string();
+
+    // Labelled by route template rather than path to keep the number of series bounded
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+
+    let span = info_span!(
+        "request",
+        method = %method,
+        route = %route,
+        request_id,
+        user_id = Empty,
+        status = Empty,
+        latency_ms = Empty,
+    );
+
+    let started_at = Instant::now();
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request).instrument(span.clone()))
+        .await;
+
+    let latency = started_at.elapsed();
+    let status = response.status().as_u16();
+
+    span.record("status", status);
+    span.record("latency_ms", latency.as_millis() as u64);
+    span.in_scope(|| info!("finished request"));
+
+    counter!(
+        HTTP_REQUESTS_TOTAL,
+        "method" => method.clone(),
+        "route" => route.clone(),
+        "status" => status.to_string(),
+    )
+    .increment(1);
+    histogram!(HTTP_REQUEST_DURATION_SECONDS, "method" => method, "route" => route)
+        .record(latency.as_secs_f64());
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(X_REQUEST_ID, value);
+    }
+
+    response
+}
+
+/// Client supplied ids end up in logs, so only short, plain ones are accepted.
+fn is_valid_request_id(request_id: &str) -> bool {
+    (1..=128).contains(&request_id.len())
+        && request_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
 
 pub async fn inject_user_data(
     State(pool): State<DbPool>,
@@ -16,53 +111,329 @@ pub async fn inject_user_data(
 ) -> Result<impl IntoResponse, HandlerError> {
     debug!("->> {:<12} - inject_user_data", "MIDDLEWARE");
 
-    if let Some(session_token) = cookies.get(SESSION_TOKEN).map(|c| c.value().to_string()) {
-        let ids = sessions_service::get_ids_by_token(&pool, session_token)
-            .await
-            .map_err(HandlerError::CarSharingError);
+    // Machine clients authenticate with a bearer token, browsers with the session cookie
+    let bearer_token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(String::from);
+
+    let user_data = match (bearer_token, cookies.get(SESSION_TOKEN)) {
+        (Some(bearer_token), _) => {
+            api_tokens_service::get_user_data_by_token(&pool, &bearer_token)
+                .await
+                .ok()
+        }
+        (None, Some(cookie)) => {
+            let user_data = sessions_service::get_ids_by_token(&pool, cookie.value().to_string())
+                .await
+                .ok();
+
+            // Blocked users lose all their sessions
+            if let Some(user_data) = user_data.as_ref().filter(|u| u.status == UserStatus::Blocked) {
+                sessions_service::delete_user_sessions(&pool, user_data.user_id).await?;
+                cookies.remove(removal_session_cookie().await);
+            }
+
+            user_data
+        }
+        (None, None) => None,
+    };
+
+    if let Some(user_data) = user_data.filter(|u| u.status != UserStatus::Blocked) {
+        Span::current().record("user_id", display(user_data.user_id));
+        request.extensions_mut().insert(user_data);
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Rejects cross-site mutating requests by checking their `Origin`, or `Referer` when
+/// the origin is missing, against the API's own origin and the trusted origins. The own
+/// origin is served over HTTPS when cookies are secure, over HTTP otherwise. Requests
+/// without either header don't come from a browser and are let through.
+pub async fn csrf_protection(
+    request: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, HandlerError> {
+    debug!("->> {:<12} - csrf_protection", "MIDDLEWARE");
+
+    if request.method().is_safe() {
+        return Ok(next.run(request).await);
+    }
+
+    let headers = request.headers();
+    let header_value = |name| headers.get(name).and_then(|value: &HeaderValue| value.to_str().ok());
+
+    let origin = header_value(ORIGIN).or_else(|| header_value(REFERER).and_then(origin_of));
+
+    if let Some(origin) = origin {
+        let config = config().await;
+
+        let scheme = if config.cookie_secure() { "https" } else { "http" };
+
+        if !is_trusted_origin(origin, scheme, header_value(HOST), config.csrf_trusted_origins()) {
+            return Err(HandlerError::CsrfError);
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Extracts `scheme://host[:port]` from a URL.
+fn origin_of(url: &str) -> Option<&str> {
+    let host_start = url.find("://")? + 3;
+    let host_end = url[host_start..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |end| host_start + end);
+
+    Some(&url[..host_end])
+}
+
+fn is_trusted_origin(
+    origin: &str,
+    scheme: &str,
+    host: Option<&str>,
+    trusted_origins: &[String],
+) -> bool {
+    trusted_origins.iter().any(|trusted| trusted == origin)
+        || host.is_some_and(|host| origin.split_once("://") == Some((scheme, host)))
+}
+
+/// Marks responses of the unversioned `/api` alias as deprecated, linking to
+/// the same route under `/api/v1`.
+pub async fn deprecated_alias(request: Request<Body>, next: Next) -> impl IntoResponse {
+    debug!("->> {:<12} - deprecated_alias", "MIDDLEWARE");
+
+    let successor = format!("</api/v1{}>; rel=\"successor-version\"", request.uri().path());
+
+    let mut response = next.run(request).await;
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.insert(LINK, link);
+    }
+
+    response
+}
+
+/// Answers with a timeout error when the handler takes longer than the configured
+/// request timeout.
+pub async fn request_timeout(
+    request: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, HandlerError> {
+    let timeout = Duration::from_secs(config().await.request_timeout());
+
+    tokio::time::timeout(timeout, next.run(request))
+        .await
+        .map_err(|_| HandlerError::Timeout)
+}
+
+/// Counts requests per client IP in fixed windows.
+pub struct RateLimiter {
+    requests: u32,
+    window: Duration,
+    clients: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests: u32, window: Duration) -> Self {
+        RateLimiter {
+            requests,
+            window,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request, returning the seconds until the next window when the client is
+    /// over the limit.
+    fn check(&self, ip: IpAddr, now: Instant) -> Result<(), u64> {
+        let mut clients = self.clients.lock().unwrap_or_else(|err| err.into_inner());
 
-        if let Ok(ids) = ids {
-            request.extensions_mut().insert(UserData {
-                telegram_id: ids.0,
-                user_id: ids.1,
-            });
+        // Forget clients whose window is over, so the map doesn't grow without bounds
+        if clients.len() > 10_000 {
+            clients.retain(|_, (started_at, _)| now.duration_since(*started_at) < self.window);
         }
+
+        let (started_at, count) = clients.entry(ip).or_insert((now, 0));
+
+        if now.duration_since(*started_at) >= self.window {
+            *started_at = now;
+            *count = 0;
+        }
+
+        if *count >= self.requests {
+            let retry_after = self.window - now.duration_since(*started_at);
+            return Err(retry_after.as_secs().max(1));
+        }
+
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Health probes and metrics scrapers poll from a single address, and mustn't be cut off.
+fn is_rate_limited(path: &str) -> bool {
+    !path.starts_with("/health/") && path != "/metrics"
+}
+
+/// Limits requests by peer address, so behind a proxy every client shares the proxy's limit.
+pub async fn rate_limit(
+    State(rate_limiter): State<Arc<RateLimiter>>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<impl IntoResponse, HandlerError> {
+    // Only missing when the app isn't served with connect info, e.g. in tests
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(address)| address.ip());
+
+    if let Some(ip) = ip.filter(|_| is_rate_limited(request.uri().path())) {
+        rate_limiter
+            .check(ip, Instant::now())
+            .map_err(HandlerError::RateLimited)?;
     }
 
     Ok(next.run(request).await)
 }
 
+/// Lets in any signed-in user, as long as the API token used, if any, has one of the
+/// route's scopes.
 pub async fn require_auth(
+    State(scopes): State<&'static [Permission]>,
     req: Request<Body>,
     next: Next,
 ) -> Result<impl IntoResponse, HandlerError> {
     debug!("->> {:<12} - require_auth", "MIDDLEWARE");
 
-    if req.extensions().get::<UserData>().is_some() {
-        Ok(next.run(req).await)
-    } else {
-        Ok(Redirect::to("/api/login").into_response())
+    match req.extensions().get::<UserData>() {
+        Some(user_data) if scopes.iter().any(|scope| user_data.has_scope(*scope)) => {
+            Ok(next.run(req).await)
+        }
+        Some(_) => Err(HandlerError::PermissionError),
+        None => Err(HandlerError::Unauthorized),
     }
 }
 
-pub async fn require_admin(
-    State(pool): State<DbPool>,
+pub async fn require_permission(
+    State(permission): State<Permission>,
     req: Request<Body>,
     next: Next,
 ) -> Result<impl IntoResponse, HandlerError> {
-    debug!("->> {:<12} - require_admin", "MIDDLEWARE");
+    debug!("->> {:<12} - require_permission", "MIDDLEWARE");
 
-    if let Some(user_data) = req.extensions().get::<UserData>() {
-        let is_admin = users_service::check_if_admin(&pool, user_data.user_id)
-            .await
-            .map_err(HandlerError::CarSharingError)?;
+    match req.extensions().get::<UserData>() {
+        Some(user_data) if user_data.has_permission(permission) => Ok(next.run(req).await),
+        Some(_) => Err(HandlerError::PermissionError),
+        None => Err(HandlerError::Unauthorized),
+    }
+}
 
-        if is_admin {
-            Ok(next.run(req).await)
-        } else {
-            Ok(Redirect::to("/api/login").into_response())
-        }
-    } else {
-        Ok(Redirect::to("/api/login").into_response())
+#[cfg(test)]
+mod tests {
+    use axum::{Extension, middleware, Router};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use tower::ServiceExt;
+
+    use crate::models::role::Role;
+
+    use super::*;
+
+    async fn place_order_status(scopes: Option<Vec<Permission>>) -> StatusCode {
+        let user_data = UserData {
+            telegram_id: 443621429,
+            user_id: Uuid::new_v4(),
+            session_id: None,
+            api_token_id: None,
+            scopes,
+            role: Role::User,
+            status: UserStatus::Active,
+        };
+
+        let app = Router::new()
+            .route("/orders", post(|| async { "Ordered" }))
+            .route_layer(middleware::from_fn_with_state(
+                &[Permission::MyOrdersWrite][..],
+                require_auth,
+            ))
+            .layer(Extension(user_data));
+
+        let request = Request::post("/orders").body(Body::empty()).unwrap();
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_require_auth_scopes() {
+        // Session cookies aren't scoped
+        assert_eq!(place_order_status(None).await, StatusCode::OK);
+        assert_eq!(
+            place_order_status(Some(vec![Permission::MyOrdersWrite])).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            place_order_status(Some(vec![Permission::CarsRead])).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[test]
+    fn test_is_valid_request_id() {
+        assert!(is_valid_request_id("3f2b8c1e-0a6d-4e59-9d1c-2f7e5b8a9c10"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("id\nwith a newline"));
+        assert!(!is_valid_request_id(&"a".repeat(129)));
+    }
+
+    #[test]
+    fn test_origin_of() {
+        assert_eq!(
+            origin_of("https://example.com:8080/api/orders?id=1"),
+            Some("https://example.com:8080")
+        );
+        assert_eq!(origin_of("https://example.com"), Some("https://example.com"));
+        assert_eq!(origin_of("example.com/api"), None);
+    }
+
+    #[test]
+    fn test_is_trusted_origin() {
+        let trusted_origins = vec!["https://app.example.com".to_string()];
+
+        let host = Some("api.example.com");
+
+        assert!(is_trusted_origin("https://api.example.com", "https", host, &trusted_origins));
+        assert!(is_trusted_origin("http://api.example.com", "http", host, &trusted_origins));
+        assert!(!is_trusted_origin("http://api.example.com", "https", host, &trusted_origins));
+        assert!(!is_trusted_origin("https://api.example.com", "https", None, &trusted_origins));
+        assert!(is_trusted_origin("https://app.example.com", "https", host, &trusted_origins));
+        assert!(!is_trusted_origin("https://evil.example.com", "https", host, &trusted_origins));
+        assert!(!is_trusted_origin("null", "https", host, &trusted_origins));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let rate_limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let client = IpAddr::from([10, 0, 0, 1]);
+        let other_client = IpAddr::from([10, 0, 0, 2]);
+        let now = Instant::now();
+
+        assert!(rate_limiter.check(client, now).is_ok());
+        assert!(rate_limiter.check(client, now).is_ok());
+        assert_eq!(Err(50), rate_limiter.check(client, now + Duration::from_secs(10)));
+        assert!(rate_limiter.check(other_client, now).is_ok());
+        assert!(rate_limiter.check(client, now + Duration::from_secs(60)).is_ok());
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(is_rate_limited("/api/v1/cars"));
+        assert!(is_rate_limited("/metrics/other"));
+        assert!(!is_rate_limited("/health/live"));
+        assert!(!is_rate_limited("/health/ready"));
+        assert!(!is_rate_limited("/metrics"));
     }
 }
@@ -0,0 +1,31 @@
+use crate::error::{CarSharingError, Result};
+use crate::models::session_token::SessionToken;
+
+/// Prefix making API tokens recognizable, e.g. by secret scanners.
+const API_TOKEN_PREFIX: &str = "cs_";
+
+impl ApiToken {
+    pub fn generate_new() -> Self {
+        Self(SessionToken::generate_new())
+    }
+
+    pub fn from_header_value(value: &str) -> Result<Self> {
+        let value = value
+            .strip_prefix(API_TOKEN_PREFIX)
+            .ok_or(CarSharingError::InvalidSessionToken)?;
+
+        Ok(Self(SessionToken::from_cookie_value(value)?))
+    }
+
+    pub fn into_header_value(self) -> String {
+        format!("{}{}", API_TOKEN_PREFIX, self.0.into_cookie_value())
+    }
+
+    pub fn into_database_value(self) -> Vec<u8> {
+        self.0.into_database_value()
+    }
+}
+
+/// Token for machine clients, stored hashed like session tokens.
+#[derive(Clone, Copy, Debug)]
+pub struct ApiToken(SessionToken);
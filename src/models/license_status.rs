@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseStatus {
+    /// Submitted and waiting for an admin's review.
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+    /// Approved, but past its expiry date.
+    Expired,
+}
+
+impl LicenseStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LicenseStatus::Pending => "pending",
+            LicenseStatus::Approved => "approved",
+            LicenseStatus::Rejected => "rejected",
+            LicenseStatus::Expired => "expired",
+        }
+    }
+
+    /// Status in effect on `today`, treating approved licenses past their expiry as expired.
+    pub fn effective(status: &str, expires_at: NaiveDate, today: NaiveDate) -> Self {
+        match LicenseStatus::from(status) {
+            LicenseStatus::Approved if expires_at < today => LicenseStatus::Expired,
+            status => status,
+        }
+    }
+}
+
+impl From<&str> for LicenseStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "approved" => LicenseStatus::Approved,
+            "rejected" => LicenseStatus::Rejected,
+            "expired" => LicenseStatus::Expired,
+            _ => LicenseStatus::Pending,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_effective_status() {
+        let today = chrono::Utc::now().date_naive();
+
+        assert_eq!(
+            LicenseStatus::effective("approved", today, today),
+            LicenseStatus::Approved
+        );
+        assert_eq!(
+            LicenseStatus::effective("approved", today - Duration::days(1), today),
+            LicenseStatus::Expired
+        );
+        assert_eq!(
+            LicenseStatus::effective("rejected", today - Duration::days(1), today),
+            LicenseStatus::Rejected
+        );
+    }
+}
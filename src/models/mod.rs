@@ -1,47 +1,273 @@
+use axum::extract::rejection::JsonRejection;
+use axum::http::header::RETRY_AFTER;
 use axum::http::StatusCode;
 use axum::Json;
 use axum::response::{IntoResponse, Response};
-use serde_json::json;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::error;
+use utoipa::ToSchema;
 
 use crate::error::CarSharingError;
+use crate::middlewares::current_request_id;
 
+pub mod api_token;
+pub mod license_status;
+pub mod permission;
+pub mod role;
 pub mod session_token;
+pub mod user_status;
 
 #[derive(Debug, strum_macros::AsRefStr)]
 pub enum HandlerError {
     TelegramHashProblem,
+    TelegramAuthExpired,
+    Unauthorized,
     OwnershipError,
+    PermissionError,
+    /// Admins can't change their own role or status.
+    OwnAccount,
+    CsrfError,
+    UserBlocked,
+    UserRestricted,
+    LicenseRequired,
+    NotFound,
+    /// Too many requests, carries the seconds until the client may retry.
+    RateLimited(u64),
+    Timeout,
+    ValidationError(Vec<FieldError>),
+    JsonRejection(JsonRejection),
     CarSharingError(CarSharingError),
 }
 
+/// Body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    /// Stable, machine-readable error code, e.g. `not_found`.
+    pub code: &'static str,
+    pub message: &'static str,
+    pub happened_at: DateTime<Utc>,
+    /// Id of the failed request, to be quoted when reporting errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+}
+
+/// Why a single request field was rejected.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: &str) -> Self {
+        FieldError {
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
 impl From<CarSharingError> for HandlerError {
     fn from(value: CarSharingError) -> Self {
         HandlerError::CarSharingError(value)
     }
 }
 
-impl IntoResponse for HandlerError {
-    fn into_response(self) -> Response {
-        let (status, err_msg) = match self {
-            Self::CarSharingError(db_error) => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Internal server error: {}", db_error),
+impl HandlerError {
+    /// Status code, machine-readable error code and message shown to clients.
+    fn parts(&self) -> (StatusCode, &'static str, &'static str) {
+        match self {
+            Self::TelegramHashProblem => (
+                StatusCode::UNAUTHORIZED,
+                "invalid_telegram_login",
+                "Telegram login data is invalid",
+            ),
+            Self::TelegramAuthExpired => (
+                StatusCode::UNAUTHORIZED,
+                "telegram_login_expired",
+                "Telegram login data is outdated, please log in again",
+            ),
+            Self::Unauthorized | Self::CarSharingError(CarSharingError::InvalidSessionToken) => (
+                StatusCode::UNAUTHORIZED,
+                "unauthorized",
+                "authentication is required for this action",
             ),
             Self::OwnershipError => (
                 StatusCode::FORBIDDEN,
-                String::from("you don't have access to this action"),
+                "not_owner",
+                "you don't have access to this action",
+            ),
+            Self::PermissionError => (
+                StatusCode::FORBIDDEN,
+                "permission_denied",
+                "you don't have permission for this action",
+            ),
+            Self::OwnAccount => (
+                StatusCode::FORBIDDEN,
+                "own_account",
+                "you can't change your own role or status",
+            ),
+            Self::CarSharingError(CarSharingError::OwnLicense) => (
+                StatusCode::FORBIDDEN,
+                "own_license",
+                "you can't review your own driver's license",
+            ),
+            Self::CsrfError => (
+                StatusCode::FORBIDDEN,
+                "csrf_rejected",
+                "cross-site request rejected",
+            ),
+            Self::UserBlocked => (
+                StatusCode::FORBIDDEN,
+                "user_blocked",
+                "your account is blocked",
+            ),
+            Self::UserRestricted => (
+                StatusCode::FORBIDDEN,
+                "user_restricted",
+                "your account is not allowed to place orders",
+            ),
+            Self::LicenseRequired => (
+                StatusCode::FORBIDDEN,
+                "license_required",
+                "a verified, unexpired driver's license is required to place orders",
+            ),
+            Self::NotFound | Self::CarSharingError(CarSharingError::DatabaseNotFound) => (
+                StatusCode::NOT_FOUND,
+                "not_found",
+                "the requested resource was not found",
+            ),
+            Self::RateLimited(_) => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                "too many requests, please retry later",
+            ),
+            Self::Timeout => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "timeout",
+                "the request took too long to process",
+            ),
+            Self::ValidationError(_) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "validation_failed",
+                "the request contains invalid fields",
             ),
-            _ => (
+            Self::JsonRejection(rejection) => (
+                rejection.status(),
+                "invalid_body",
+                "the request body is invalid",
+            ),
+            Self::CarSharingError(CarSharingError::DatabaseConflict) => (
+                StatusCode::CONFLICT,
+                "conflict",
+                "the request conflicts with the current state of the resource",
+            ),
+            Self::CarSharingError(CarSharingError::LastAdmin) => (
+                StatusCode::CONFLICT,
+                "last_admin",
+                "at least one active admin must remain",
+            ),
+            Self::CarSharingError(CarSharingError::LicenseNotPending) => (
+                StatusCode::CONFLICT,
+                "license_not_pending",
+                "only pending driver's licenses can be reviewed",
+            ),
+            Self::CarSharingError(CarSharingError::OrderClosed) => (
+                StatusCode::CONFLICT,
+                "order_closed",
+                "the order is already finished or cancelled",
+            ),
+            Self::CarSharingError(CarSharingError::DatabaseDieselError(_)) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                String::from("Internal server error"),
+                "internal_error",
+                "internal server error",
             ),
+        }
+    }
+}
+
+impl IntoResponse for HandlerError {
+    fn into_response(self) -> Response {
+        let (status, code, message) = self.parts();
+
+        let mut body = ErrorResponse {
+            code,
+            message,
+            happened_at: Utc::now(),
+            request_id: current_request_id(),
+            errors: Vec::new(),
         };
 
-        (status,
-         Json(
-             json!({"resource":"PostModel", "message": err_msg, "happened_at" : chrono::Utc::now() }),
-         ),
-        )
-            .into_response()
+        match self {
+            Self::RateLimited(retry_after) => {
+                return (status, [(RETRY_AFTER, retry_after.to_string())], Json(body)).into_response();
+            }
+            Self::ValidationError(field_errors) => {
+                body.errors = field_errors;
+            }
+            Self::JsonRejection(rejection) => {
+                body.errors = vec![FieldError::new("body", &rejection.body_text())];
+            }
+            // Internals are only logged, the request id lets support find them
+            Self::CarSharingError(err) if status == StatusCode::INTERNAL_SERVER_ERROR => {
+                error!("->> {:<12} - {}", "ERROR", err);
+            }
+            _ => {}
+        }
+
+        (status, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::to_bytes;
+    use serde_json::Value;
+
+    use super::*;
+
+    async fn response_parts(error: HandlerError) -> (StatusCode, Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_not_found_and_conflict() {
+        let (status, body) =
+            response_parts(HandlerError::CarSharingError(CarSharingError::DatabaseNotFound)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "not_found");
+
+        let (status, body) =
+            response_parts(HandlerError::CarSharingError(CarSharingError::DatabaseConflict)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["code"], "conflict");
+    }
+
+    #[tokio::test]
+    async fn test_internal_error_hides_details() {
+        let error = CarSharingError::DatabaseDieselError(diesel::result::Error::RollbackTransaction);
+
+        let (status, body) = response_parts(HandlerError::CarSharingError(error)).await;
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["message"], "internal server error");
+        assert!(!body.to_string().contains("rollback"));
+    }
+
+    #[tokio::test]
+    async fn test_validation_error_lists_fields() {
+        let error = HandlerError::ValidationError(vec![FieldError::new("name", "must not be empty")]);
+
+        let (status, body) = response_parts(error).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["errors"][0]["field"], "name");
     }
 }
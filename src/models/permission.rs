@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use utoipa::{PartialSchema, ToSchema};
+use utoipa::openapi::{ObjectBuilder, RefOr, Schema, Type};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Permission {
+    CarsRead,
+    CarsWrite,
+    CarsDelete,
+    OrdersRead,
+    OrdersAccept,
+    OrdersCancel,
+    OrdersRent,
+    OrdersDelete,
+    PaymentsRecord,
+    UsersRead,
+    UsersManage,
+    ApiTokensManage,
+    MetricsRead,
+    ReportsRead,
+    // Scopes of the user's own profile, sessions and orders. Roles don't grant them,
+    // every signed-in user has them, they only restrict what an API token may do.
+    ProfileRead,
+    ProfileWrite,
+    SessionsRead,
+    SessionsWrite,
+    MyOrdersRead,
+    MyOrdersWrite,
+}
+
+impl Permission {
+    /// Permissions over other users' data, granted by roles.
+    pub const STAFF: &'static [Permission] = &[
+        Permission::CarsRead,
+        Permission::CarsWrite,
+        Permission::CarsDelete,
+        Permission::OrdersRead,
+        Permission::OrdersAccept,
+        Permission::OrdersCancel,
+        Permission::OrdersRent,
+        Permission::OrdersDelete,
+        Permission::PaymentsRecord,
+        Permission::UsersRead,
+        Permission::UsersManage,
+        Permission::ApiTokensManage,
+        Permission::MetricsRead,
+        Permission::ReportsRead,
+    ];
+
+    pub const ALL: &'static [Permission] = &[
+        Permission::CarsRead,
+        Permission::CarsWrite,
+        Permission::CarsDelete,
+        Permission::OrdersRead,
+        Permission::OrdersAccept,
+        Permission::OrdersCancel,
+        Permission::OrdersRent,
+        Permission::OrdersDelete,
+        Permission::PaymentsRecord,
+        Permission::UsersRead,
+        Permission::UsersManage,
+        Permission::ApiTokensManage,
+        Permission::MetricsRead,
+        Permission::ReportsRead,
+        Permission::ProfileRead,
+        Permission::ProfileWrite,
+        Permission::SessionsRead,
+        Permission::SessionsWrite,
+        Permission::MyOrdersRead,
+        Permission::MyOrdersWrite,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::CarsRead => "cars:read",
+            Permission::CarsWrite => "cars:write",
+            Permission::CarsDelete => "cars:delete",
+            Permission::OrdersRead => "orders:read",
+            Permission::OrdersAccept => "orders:accept",
+            Permission::OrdersCancel => "orders:cancel",
+            Permission::OrdersRent => "orders:rent",
+            Permission::OrdersDelete => "orders:delete",
+            Permission::PaymentsRecord => "payments:record",
+            Permission::UsersRead => "users:read",
+            Permission::UsersManage => "users:manage",
+            Permission::ApiTokensManage => "api_tokens:manage",
+            Permission::MetricsRead => "metrics:read",
+            Permission::ReportsRead => "reports:read",
+            Permission::ProfileRead => "profile:read",
+            Permission::ProfileWrite => "profile:write",
+            Permission::SessionsRead => "sessions:read",
+            Permission::SessionsWrite => "sessions:write",
+            Permission::MyOrdersRead => "my_orders:read",
+            Permission::MyOrdersWrite => "my_orders:write",
+        }
+    }
+}
+
+impl FromStr for Permission {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Permission::ALL
+            .iter()
+            .find(|permission| permission.as_str() == value)
+            .copied()
+            .ok_or_else(|| format!("unknown permission `{}`", value))
+    }
+}
+
+impl Serialize for Permission {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Permission {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Documented as the strings clients send and receive, not the variant names.
+impl PartialSchema for Permission {
+    fn schema() -> RefOr<Schema> {
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .enum_values(Some(Permission::ALL.iter().map(Permission::as_str)))
+            .into()
+    }
+}
+
+impl ToSchema for Permission {}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::permission::Permission;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    #[default]
+    User,
+    Admin,
+    FleetManager,
+    Accountant,
+    Support,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+            Role::FleetManager => "fleet_manager",
+            Role::Accountant => "accountant",
+            Role::Support => "support",
+        }
+    }
+
+    pub fn permissions(&self) -> &'static [Permission] {
+        match self {
+            Role::User => &[],
+            Role::Admin => Permission::STAFF,
+            Role::FleetManager => &[
+                Permission::CarsRead,
+                Permission::CarsWrite,
+                Permission::CarsDelete,
+            ],
+            Role::Accountant => &[
+                Permission::CarsRead,
+                Permission::OrdersRead,
+                Permission::PaymentsRecord,
+                Permission::ReportsRead,
+            ],
+            Role::Support => &[Permission::OrdersRead, Permission::OrdersCancel],
+        }
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+impl From<&str> for Role {
+    /// Unknown roles stored in the database get the least privileges.
+    fn from(value: &str) -> Self {
+        match value {
+            "admin" => Role::Admin,
+            "fleet_manager" => Role::FleetManager,
+            "accountant" => Role::Accountant,
+            "support" => Role::Support,
+            _ => Role::User,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roles_are_stored_as_strings() {
+        for role in [
+            Role::User,
+            Role::Admin,
+            Role::FleetManager,
+            Role::Accountant,
+            Role::Support,
+        ] {
+            assert_eq!(Role::from(role.as_str()), role);
+        }
+
+        assert_eq!(Role::from("superuser"), Role::User);
+    }
+
+    #[test]
+    fn test_staff_permissions() {
+        assert!(Role::FleetManager.has_permission(Permission::CarsWrite));
+        assert!(!Role::FleetManager.has_permission(Permission::OrdersRead));
+
+        assert!(Role::Accountant.has_permission(Permission::PaymentsRecord));
+        assert!(!Role::Accountant.has_permission(Permission::CarsDelete));
+
+        assert!(Role::Support.has_permission(Permission::OrdersCancel));
+        assert!(!Role::Support.has_permission(Permission::OrdersAccept));
+
+        assert!(Role::Admin.has_permission(Permission::UsersManage));
+        assert!(!Role::Admin.has_permission(Permission::ProfileWrite));
+        assert!(Role::User.permissions().is_empty());
+    }
+}
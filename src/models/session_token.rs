@@ -1,22 +1,57 @@
-use rand_core::RngCore;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 
-use crate::infra::Random;
+use crate::error::{CarSharingError, Result};
+
+/// Size of a session token in bytes (256 bits).
+const SESSION_TOKEN_LEN: usize = 32;
 
 impl SessionToken {
-    pub fn generate_new(random: Random) -> Self {
-        let mut u128_pool = [0u8; 16];
-        random.lock().unwrap().fill_bytes(&mut u128_pool);
-        Self(u128::from_le_bytes(u128_pool))
+    pub fn generate_new() -> Self {
+        let mut bytes = [0u8; SESSION_TOKEN_LEN];
+        OsRng.fill_bytes(&mut bytes);
+        Self(bytes)
+    }
+
+    pub fn from_cookie_value(value: &str) -> Result<Self> {
+        let mut bytes = [0u8; SESSION_TOKEN_LEN];
+        hex::decode_to_slice(value, &mut bytes)
+            .map_err(|_| CarSharingError::InvalidSessionToken)?;
+        Ok(Self(bytes))
     }
 
     pub fn into_cookie_value(self) -> String {
-        self.0.to_string()
+        hex::encode(self.0)
     }
 
+    /// Only the SHA-256 digest of the token is stored, so a leaked database
+    /// doesn't allow hijacking sessions.
     pub fn into_database_value(self) -> Vec<u8> {
-        self.0.to_le_bytes().to_vec()
+        Sha256::digest(self.0).to_vec()
     }
 }
 
 #[derive(Clone, Copy, Debug)]
-pub struct SessionToken(u128);
+pub struct SessionToken([u8; SESSION_TOKEN_LEN]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_value_round_trip() {
+        let session_token = SessionToken::generate_new();
+
+        let parsed = SessionToken::from_cookie_value(&session_token.into_cookie_value())
+            .expect("Failed to parse a session token");
+
+        assert_eq!(parsed.into_database_value(), session_token.into_database_value());
+        assert_ne!(session_token.into_database_value(), session_token.0.to_vec());
+    }
+
+    #[test]
+    fn test_invalid_cookie_value() {
+        assert!(SessionToken::from_cookie_value("1234").is_err());
+        assert!(SessionToken::from_cookie_value(&"zz".repeat(SESSION_TOKEN_LEN)).is_err());
+    }
+}
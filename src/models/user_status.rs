@@ -0,0 +1,64 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    #[default]
+    Active,
+    /// Can use the account but can't place new orders.
+    Restricted,
+    /// Can't log in at all.
+    Blocked,
+}
+
+impl UserStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserStatus::Active => "active",
+            UserStatus::Restricted => "restricted",
+            UserStatus::Blocked => "blocked",
+        }
+    }
+
+    /// Status in effect at `now`, treating expired restrictions as lifted.
+    pub fn effective(status: &str, status_until: Option<NaiveDateTime>, now: NaiveDateTime) -> Self {
+        match status_until {
+            Some(until) if until <= now => UserStatus::Active,
+            _ => UserStatus::from(status),
+        }
+    }
+}
+
+impl From<&str> for UserStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "restricted" => UserStatus::Restricted,
+            "blocked" => UserStatus::Blocked,
+            _ => UserStatus::Active,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_effective_status() {
+        let now = chrono::Utc::now().naive_utc();
+
+        assert_eq!(UserStatus::effective("blocked", None, now), UserStatus::Blocked);
+        assert_eq!(
+            UserStatus::effective("restricted", Some(now + Duration::hours(1)), now),
+            UserStatus::Restricted
+        );
+        assert_eq!(
+            UserStatus::effective("blocked", Some(now - Duration::hours(1)), now),
+            UserStatus::Active
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use utoipa::{Modify, OpenApi};
+use utoipa::openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme};
+
+use crate::handlers::{
+    api_tokens, auth, cars, dashboard, health, licenses, metrics, orders, reports, sessions, users,
+};
+use crate::handlers::auth::SESSION_TOKEN;
+use crate::models::ErrorResponse;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Car sharing API"),
+    paths(
+        auth::login::login,
+        auth::logout::logout,
+        users::get_me::get_me,
+        users::update_me::update_me,
+        licenses::get_my_license::get_my_license,
+        licenses::submit_license::submit_license,
+        sessions::list_sessions::list_sessions,
+        sessions::revoke_all_sessions::revoke_all_sessions,
+        sessions::revoke_session::revoke_session,
+        users::list_users::list_users,
+        users::get_user::get_user,
+        users::update_role::update_role,
+        users::block_user::block_user,
+        users::unblock_user::unblock_user,
+        licenses::list_licenses::list_licenses,
+        licenses::get_license::get_license,
+        licenses::approve_license::approve_license,
+        licenses::reject_license::reject_license,
+        api_tokens::create_api_token::create_api_token,
+        api_tokens::list_api_tokens::list_api_tokens,
+        api_tokens::revoke_api_token::revoke_api_token,
+        cars::create_car::create_car,
+        cars::list_cars::list_cars,
+        cars::get_car::get_car,
+        cars::update_car::update_car,
+        cars::delete_car::delete_car,
+        cars::import_cars::import_cars,
+        cars::export_cars::export_cars,
+        orders::make_order::make_order,
+        orders::orders_history::orders_history,
+        orders::cancel_order::cancel_order,
+        orders::list_orders::list_orders,
+        orders::get_order::get_order,
+        orders::accept_order::accept_order,
+        orders::start_rent::start_rent,
+        orders::set_due_at::set_due_at,
+        orders::finish_rent::finish_rent,
+        orders::set_paid::set_paid,
+        orders::delete_order::delete_order,
+        orders::order_events::order_events,
+        orders::my_order_events::my_order_events,
+        reports::get_revenue_report::get_revenue_report,
+        reports::get_utilization_report::get_utilization_report,
+        dashboard::get_dashboard::get_dashboard,
+        metrics::get_metrics::get_metrics,
+        health::get_liveness::get_liveness,
+        health::get_readiness::get_readiness,
+    ),
+    components(schemas(ErrorResponse)),
+    modifiers(&SecurityAddon),
+)]
+pub struct ApiDoc;
+
+/// Browsers authenticate with the session cookie, machine clients with an API token.
+struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+
+        components.add_security_scheme(
+            "session_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new(SESSION_TOKEN))),
+        );
+        components.add_security_scheme(
+            "api_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_documents_errors_and_security() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        assert!(spec["paths"]["/api/v1/cars/{id}"]["get"].is_object());
+        assert!(spec["components"]["schemas"]["ErrorResponse"].is_object());
+        assert!(spec["components"]["schemas"]["CarResponse"].is_object());
+        assert_eq!(
+            spec["components"]["securitySchemes"]["session_cookie"]["name"],
+            SESSION_TOKEN
+        );
+    }
+}
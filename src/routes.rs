@@ -1,25 +1,44 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
-    Extension, http::StatusCode, middleware, response::IntoResponse, Router, routing::get,
+    Extension, middleware, response::IntoResponse, Router, routing::get,
     routing::post,
 };
-use axum::routing::{delete, patch};
-use diesel_async::{AsyncPgConnection, pooled_connection::AsyncDieselConnectionManager};
-use rand_chacha::ChaCha8Rng;
-use rand_core::{OsRng, RngCore, SeedableRng};
+use axum::http::{HeaderName, HeaderValue, Method};
+use axum::http::header::{AUTHORIZATION, CONTENT_TYPE, RETRY_AFTER};
+use axum::routing::{delete, MethodRouter, patch, put};
 use tower_cookies::CookieManagerLayer;
-use tracing::log::debug;
+use tower_http::cors::CorsLayer;
+use tracing::debug;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
 
+use crate::config::Config;
+use crate::handlers::api_tokens::create_api_token::create_api_token;
+use crate::handlers::api_tokens::list_api_tokens::list_api_tokens;
+use crate::handlers::api_tokens::revoke_api_token::revoke_api_token;
 use crate::handlers::auth::login::login;
 use crate::handlers::auth::logout::logout;
 use crate::handlers::auth::UserData;
 use crate::handlers::cars::create_car::create_car;
 use crate::handlers::cars::delete_car::delete_car;
+use crate::handlers::cars::export_cars::export_cars;
 use crate::handlers::cars::get_car::get_car;
+use crate::handlers::cars::import_cars::import_cars;
 use crate::handlers::cars::list_cars::list_cars;
 use crate::handlers::cars::update_car::update_car;
 use crate::handlers::DbPool;
+use crate::handlers::dashboard::get_dashboard::get_dashboard;
+use crate::handlers::health::get_liveness::get_liveness;
+use crate::handlers::health::get_readiness::get_readiness;
+use crate::handlers::licenses::approve_license::approve_license;
+use crate::handlers::licenses::get_license::get_license;
+use crate::handlers::licenses::get_my_license::get_my_license;
+use crate::handlers::licenses::list_licenses::list_licenses;
+use crate::handlers::licenses::reject_license::reject_license;
+use crate::handlers::licenses::submit_license::submit_license;
+use crate::handlers::metrics::get_metrics::get_metrics;
 use crate::handlers::orders::accept_order::accept_order;
 use crate::handlers::orders::cancel_order::cancel_order;
 use crate::handlers::orders::delete_order::delete_order;
@@ -27,72 +46,308 @@ use crate::handlers::orders::finish_rent::finish_rent;
 use crate::handlers::orders::get_order::get_order;
 use crate::handlers::orders::list_orders::list_orders;
 use crate::handlers::orders::make_order::make_order;
+use crate::handlers::orders::my_order_events::my_order_events;
+use crate::handlers::orders::order_events::order_events;
 use crate::handlers::orders::orders_history::orders_history;
+use crate::handlers::orders::set_due_at::set_due_at;
 use crate::handlers::orders::set_paid::set_paid;
 use crate::handlers::orders::start_rent::start_rent;
-use crate::infra::db::run_migrations;
-use crate::middlewares::{inject_user_data, require_admin, require_auth};
+use crate::handlers::reports::get_revenue_report::get_revenue_report;
+use crate::handlers::reports::get_utilization_report::get_utilization_report;
+use crate::handlers::sessions::list_sessions::list_sessions;
+use crate::handlers::sessions::revoke_all_sessions::revoke_all_sessions;
+use crate::handlers::sessions::revoke_session::revoke_session;
+use crate::handlers::users::block_user::block_user;
+use crate::handlers::users::get_me::get_me;
+use crate::handlers::users::get_user::get_user;
+use crate::handlers::users::list_users::list_users;
+use crate::handlers::users::unblock_user::unblock_user;
+use crate::handlers::users::update_me::update_me;
+use crate::handlers::users::update_role::update_role;
+use crate::infra::order_events::OrderEvents;
+use crate::middlewares::{
+    csrf_protection, deprecated_alias, inject_user_data, rate_limit, RateLimiter,
+    request_timeout, require_auth, require_permission, trace_request,
+};
+use crate::models::HandlerError;
+use crate::models::permission::Permission;
+use crate::openapi::ApiDoc;
 
-pub async fn app_router(db_url: &str) -> Router {
-    let random = ChaCha8Rng::seed_from_u64(OsRng.next_u64());
+pub fn app_router(config: &Config, pool: DbPool, order_events: OrderEvents) -> Router {
     let user_data: Option<UserData> = None;
 
-    run_migrations(db_url);
+    let api_v1 = api_v1_routes();
 
-    let manager = AsyncDieselConnectionManager::<AsyncPgConnection>::new(db_url);
-    let pool = bb8::Pool::builder().build(manager).await.unwrap();
+    let api = Router::new()
+        .nest("/v1", api_v1.clone())
+        .nest(
+            "/v2",
+            api_v2_routes().fallback_service(api_v1.clone().with_state(pool.clone())),
+        )
+        // Unversioned alias kept for clients released before versioning
+        .merge(api_v1.layer(middleware::from_fn(deprecated_alias)));
 
-    Router::new()
-        .route("/", get(root))
-        .merge(auth_routes())
-        .nest("/cars", cars_routes(pool.clone()))
-        .nest("/orders", orders_user_routes())
-        .nest("/orders", orders_admin_routes(pool.clone()))
+    let mut router = Router::new()
+        .nest("/api", api)
+        .nest("/health", health_routes());
+
+    // Without a port of their own the metrics are only served to users allowed to read them
+    if config.metrics_enabled() && config.metrics_port().is_none() {
+        router = router.route(
+            "/metrics",
+            with_permission(Permission::MetricsRead, get(get_metrics)),
+        );
+    }
+
+    if config.swagger_ui_enabled() {
+        router = router
+            .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()));
+    }
+
+    router = router
+        .fallback(handler_404)
+        .layer(middleware::from_fn(request_timeout))
         .layer(Extension(user_data))
-        .layer(Extension(Arc::new(Mutex::new(random))))
+        .layer(Extension(order_events))
         .layer(middleware::from_fn_with_state(
             pool.clone(),
             inject_user_data,
         ))
-        .layer(CookieManagerLayer::new())
+        .layer(middleware::from_fn(csrf_protection))
+        .layer(CookieManagerLayer::new());
+
+    if config.rate_limit_enabled() {
+        let rate_limiter = RateLimiter::new(
+            config.rate_limit_requests(),
+            Duration::from_secs(config.rate_limit_window()),
+        );
+
+        router = router.layer(middleware::from_fn_with_state(
+            Arc::new(rate_limiter),
+            rate_limit,
+        ));
+    }
+
+    if !config.cors_allowed_origins().is_empty() {
+        router = router.layer(cors_layer(config.cors_allowed_origins()));
+    }
+
+    router
+        .layer(middleware::from_fn(trace_request))
         .with_state(pool)
+}
+
+/// Lets browsers on the allowed origins call the API with their session cookie.
+fn cors_layer(allowed_origins: &[String]) -> CorsLayer {
+    let x_request_id = HeaderName::from_static("x-request-id");
+
+    CorsLayer::new()
+        .allow_origin(
+            allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok())
+                .collect::<Vec<HeaderValue>>(),
+        )
+        .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+        .allow_headers([CONTENT_TYPE, AUTHORIZATION, x_request_id.clone()])
+        .expose_headers([x_request_id, RETRY_AFTER])
+        .allow_credentials(true)
+}
+
+/// Served on `METRICS_PORT`, which only the metrics scraper is expected to reach.
+pub fn metrics_router(pool: DbPool) -> Router {
+    Router::new()
+        .route("/metrics", get(get_metrics))
+        .with_state(pool)
+}
+
+fn api_v1_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", get(root))
+        .merge(auth_routes())
+        .nest("/me", me_routes())
+        .nest("/sessions", sessions_routes())
+        .nest("/users", users_routes())
+        .nest("/tokens", api_tokens_routes())
+        .nest("/licenses", licenses_routes())
+        .nest("/cars", cars_routes())
+        .nest("/orders", orders_user_routes())
+        .nest("/orders", orders_admin_routes())
+        .nest("/reports", reports_routes())
+        .nest("/admin", admin_routes())
         .fallback(handler_404)
 }
 
+/// Probes for the orchestrator, outside `/api` as they aren't part of the versioned API.
+fn health_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/live", get(get_liveness))
+        .route("/ready", get(get_readiness))
+}
+
+/// Endpoints whose request or response bodies changed in v2. Their handlers belong
+/// in a `handlers::v2` module and share the services with v1, anything not routed
+/// here is served by the v1 handlers.
+fn api_v2_routes() -> Router<DbPool> {
+    Router::new()
+}
+
 fn auth_routes() -> Router<DbPool> {
     Router::new()
         .route("/login", post(login))
         .route("/logout", post(logout))
 }
 
-fn cars_routes(pool: DbPool) -> Router<DbPool> {
+fn me_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", with_scope(&[Permission::ProfileRead], get(get_me)))
+        .route("/", with_scope(&[Permission::ProfileWrite], patch(update_me)))
+        .route("/license", with_scope(&[Permission::ProfileRead], get(get_my_license)))
+        .route("/license", with_scope(&[Permission::ProfileWrite], put(submit_license)))
+}
+
+fn sessions_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", with_scope(&[Permission::SessionsRead], get(list_sessions)))
+        .route("/", with_scope(&[Permission::SessionsWrite], delete(revoke_all_sessions)))
+        .route("/:id", with_scope(&[Permission::SessionsWrite], delete(revoke_session)))
+}
+
+fn users_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", with_permission(Permission::UsersRead, get(list_users)))
+        .route("/:id", with_permission(Permission::UsersRead, get(get_user)))
+        .route(
+            "/role/:id",
+            with_permission(Permission::UsersManage, patch(update_role)),
+        )
+        .route(
+            "/block/:id",
+            with_permission(Permission::UsersManage, patch(block_user)),
+        )
+        .route(
+            "/unblock/:id",
+            with_permission(Permission::UsersManage, patch(unblock_user)),
+        )
+}
+
+fn licenses_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", with_permission(Permission::UsersRead, get(list_licenses)))
+        .route("/:id", with_permission(Permission::UsersRead, get(get_license)))
+        .route(
+            "/approve/:id",
+            with_permission(Permission::UsersManage, patch(approve_license)),
+        )
+        .route(
+            "/reject/:id",
+            with_permission(Permission::UsersManage, patch(reject_license)),
+        )
+}
+
+fn api_tokens_routes() -> Router<DbPool> {
     Router::new()
-        .route("/", post(create_car))
-        .route("/:id", get(get_car))
-        .route("/:id", patch(update_car))
-        .route("/:id", delete(delete_car))
-        .route("/", get(list_cars))
-        .route_layer(middleware::from_fn_with_state(pool, require_admin))
+        .route(
+            "/",
+            with_permission(Permission::ApiTokensManage, post(create_api_token)),
+        )
+        .route(
+            "/",
+            with_permission(Permission::ApiTokensManage, get(list_api_tokens)),
+        )
+        .route(
+            "/:id",
+            with_permission(Permission::ApiTokensManage, delete(revoke_api_token)),
+        )
+}
+
+fn cars_routes() -> Router<DbPool> {
+    Router::new()
+        .route("/", with_permission(Permission::CarsWrite, post(create_car)))
+        .route("/:id", with_permission(Permission::CarsRead, get(get_car)))
+        .route("/:id", with_permission(Permission::CarsWrite, patch(update_car)))
+        .route("/:id", with_permission(Permission::CarsDelete, delete(delete_car)))
+        .route("/", with_permission(Permission::CarsRead, get(list_cars)))
+        .route("/import", with_permission(Permission::CarsWrite, post(import_cars)))
+        .route("/export", with_permission(Permission::CarsRead, get(export_cars)))
 }
 
 fn orders_user_routes() -> Router<DbPool> {
     Router::new()
-        .route("/history", get(orders_history))
-        .route("/", post(make_order))
-        .route("/cancel/:id", patch(cancel_order))
-        .route_layer(middleware::from_fn(require_auth))
+        .route("/history", with_scope(&[Permission::MyOrdersRead], get(orders_history)))
+        .route("/", with_scope(&[Permission::MyOrdersWrite], post(make_order)))
+        .route(
+            "/cancel/:id",
+            // Also reached by staff cancelling other users' orders
+            with_scope(
+                &[Permission::MyOrdersWrite, Permission::OrdersCancel],
+                patch(cancel_order),
+            ),
+        )
+        .route(
+            "/events/mine",
+            with_scope(&[Permission::MyOrdersRead], get(my_order_events)),
+        )
 }
 
-fn orders_admin_routes(pool: DbPool) -> Router<DbPool> {
+fn orders_admin_routes() -> Router<DbPool> {
     Router::new()
-        .route("/:id", get(get_order))
-        .route("/", get(list_orders))
-        .route("/accept/:id", patch(accept_order))
-        .route("/:id", delete(delete_order))
-        .route("/finish/:id", patch(finish_rent))
-        .route("/set_paid/:id", patch(set_paid))
-        .route("/start/:id", patch(start_rent))
-        .route_layer(middleware::from_fn_with_state(pool, require_admin))
+        .route("/:id", with_permission(Permission::OrdersRead, get(get_order)))
+        .route("/", with_permission(Permission::OrdersRead, get(list_orders)))
+        .route("/events", with_permission(Permission::OrdersRead, get(order_events)))
+        .route(
+            "/accept/:id",
+            with_permission(Permission::OrdersAccept, patch(accept_order)),
+        )
+        .route("/:id", with_permission(Permission::OrdersDelete, delete(delete_order)))
+        .route(
+            "/finish/:id",
+            with_permission(Permission::OrdersRent, patch(finish_rent)),
+        )
+        .route(
+            "/set_paid/:id",
+            with_permission(Permission::PaymentsRecord, patch(set_paid)),
+        )
+        .route("/start/:id", with_permission(Permission::OrdersRent, patch(start_rent)))
+        .route("/due/:id", with_permission(Permission::OrdersRent, patch(set_due_at)))
+}
+
+fn reports_routes() -> Router<DbPool> {
+    Router::new()
+        .route(
+            "/revenue",
+            with_permission(Permission::ReportsRead, get(get_revenue_report)),
+        )
+        .route(
+            "/utilization",
+            with_permission(Permission::ReportsRead, get(get_utilization_report)),
+        )
+}
+
+fn admin_routes() -> Router<DbPool> {
+    Router::new().route(
+        "/dashboard",
+        with_permission(Permission::OrdersRead, get(get_dashboard)),
+    )
+}
+
+/// Rejects anonymous requests, and API tokens without any of the given scopes.
+fn with_scope(
+    scopes: &'static [Permission],
+    method_router: MethodRouter<DbPool>,
+) -> MethodRouter<DbPool> {
+    method_router.route_layer(middleware::from_fn_with_state(scopes, require_auth))
+}
+
+/// Rejects requests of users whose role lacks the given permission.
+fn with_permission(
+    permission: Permission,
+    method_router: MethodRouter<DbPool>,
+) -> MethodRouter<DbPool> {
+    method_router.route_layer(middleware::from_fn_with_state(
+        permission,
+        require_permission,
+    ))
 }
 
 async fn root() -> &'static str {
@@ -102,8 +357,5 @@ async fn root() -> &'static str {
 async fn handler_404() -> impl IntoResponse {
     debug!("->> {:<12} - handler_404", "HANDLER");
 
-    (
-        StatusCode::NOT_FOUND,
-        "The requested resource was not found",
-    )
+    HandlerError::NotFound
 }